@@ -1,93 +1,195 @@
-use crate::fetch::{get_disk_info, get_outdated_packages, get_packages, get_recent_packages};
+use crate::fetch::collector::{Collection, Panel, Update};
+use crate::fetch::error::{Operation, Severity};
+use crate::fetch::stats::PackageStats;
+use crate::fetch::sources::aur::remove_foreign_duplicates;
+use crate::fetch::system::{SystemField, SystemInfo, SystemReader};
+use crate::fetch::{
+    FetchError, PackageEvent, PackageInfo, PackageManager, Registry, SourceError, SystemProfile,
+};
+use crate::ui::logo::Logo;
+
+/// How many events the recent activity panels show.
+pub const RECENT_EVENTS: usize = 10;
 
 pub struct App {
+    pub registry: Registry,
+    pub profile: SystemProfile, // detected managers, updated on refresh
     pub packages: Vec<crate::fetch::PackageInfo>,
     pub outdated_packages: Vec<crate::fetch::PackageInfo>,
-    pub recent_packages: Vec<crate::fetch::PackageInfo>,
+    pub events: Vec<PackageEvent>, // package history, oldest first
     pub disk_info: Vec<crate::fetch::DiskInfo>,
-    pub error_message: Option<String>,
+    pub stats: PackageStats,
+    pub system: SystemInfo,
+    pub system_fields: Vec<SystemField>, // rows shown in the system panel
+    pub logo: Option<Logo>,              // None hides the logo
+    pub errors: Vec<SourceError>, // failures from the latest refresh
+    collection: Option<Collection>, // the refresh in progress, if any
 }
 
 impl App {
     pub fn new() -> Self {
-        let packages = get_packages();
+        Self::with_registry(Registry::default())
+    }
+
+    /// Creates the app with a custom set of package sources, waiting until
+    /// everything has been collected.
+    pub fn with_registry(registry: Registry) -> Self {
+        let mut app = Self::start(registry);
+        app.wait();
+        app
+    }
 
-        let (outdated_packages, error_message) = match get_outdated_packages() {
-            Ok(v) => (v, None),
-            Err(e) => (Vec::new(), Some(e)),
+    /// Creates an empty app and starts collecting in the background.
+    ///
+    /// Results are applied by [`App::poll`] or [`App::wait`].
+    pub fn start(registry: Registry) -> Self {
+        // Only os-release is read up front, to pick the logo
+        let distro = SystemReader::default().distro();
+        let logo = Some(Logo::for_distro(&distro));
+
+        let mut app = Self {
+            registry,
+            profile: SystemProfile::default(),
+            packages: Vec::new(),
+            outdated_packages: Vec::new(),
+            events: Vec::new(),
+            disk_info: Vec::new(),
+            stats: PackageStats::default(),
+            system: SystemInfo {
+                distro,
+                ..SystemInfo::default()
+            },
+            system_fields: SystemField::ALL.to_vec(),
+            logo,
+            errors: Vec::new(),
+            collection: None,
         };
+        app.refresh();
+        app
+    }
+
+    /// Starts collecting everything again without blocking, including
+    /// package manager detection.
+    ///
+    /// A refresh already in progress is abandoned.
+    pub fn refresh(&mut self) {
+        self.collection = Some(Collection::start(&self.registry));
+    }
 
-        let recent_packages = get_recent_packages();
-        let disk_info = get_disk_info();
+    /// Applies the results that have arrived so far.
+    ///
+    /// Returns true if anything changed.
+    pub fn poll(&mut self) -> bool {
+        let mut changed = false;
+        while let Some(update) = self.collection.as_mut().and_then(Collection::try_recv) {
+            self.apply(update);
+            changed = true;
+        }
+        self.finish_if_done();
+        changed
+    }
 
-        Self {
-            packages,
-            outdated_packages,
-            recent_packages,
-            disk_info,
-            error_message,
+    /// Blocks until the refresh in progress has finished.
+    pub fn wait(&mut self) {
+        while let Some(update) = self.collection.as_mut().and_then(Collection::recv) {
+            self.apply(update);
         }
+        self.collection = None;
+    }
+
+    /// Returns true while a refresh is in progress.
+    pub fn is_refreshing(&self) -> bool {
+        self.collection.is_some()
+    }
+
+    /// Returns true while results for `panel` are still expected.
+    pub fn is_loading(&self, panel: Panel) -> bool {
+        self.collection
+            .as_ref()
+            .is_some_and(|collection| collection.is_loading(panel))
     }
 
-    pub fn update(&mut self) {
-        self.packages = get_packages();
+    fn finish_if_done(&mut self) {
+        if self.collection.as_ref().is_some_and(Collection::is_done) {
+            self.collection = None;
+        }
+    }
 
-        match get_outdated_packages() {
-            Ok(v) => {
-                self.outdated_packages = v;
-                self.error_message = None;
+    fn apply(&mut self, update: Update) {
+        match update {
+            Update::Detected(manager, detected) => {
+                self.profile.set_detected(manager, detected);
+                return;
+            }
+            Update::Installed(manager, result) => {
+                let packages = self.record(manager, Operation::Installed, result);
+                replace_source(&mut self.packages, manager, packages);
+                // Whichever of pacman and AUR came first, drop the duplicates
+                remove_foreign_duplicates(&mut self.packages);
+            }
+            Update::Outdated(manager, result) => {
+                let packages = self.record(manager, Operation::Outdated, result);
+                replace_source(&mut self.outdated_packages, manager, packages);
             }
-            Err(e) => {
-                self.outdated_packages.clear();
-                self.error_message = Some(e);
+            Update::Events(manager, result) => {
+                let events = self.record(manager, Operation::History, result);
+                self.events.retain(|event| event.source != manager);
+                self.events.extend(events);
+                self.events.sort_by_key(|event| event.timestamp);
             }
+            Update::Disks(disks) => self.disk_info = disks,
+            Update::System(system) => self.system = *system,
         }
-
-        self.recent_packages = get_recent_packages();
-        self.disk_info = get_disk_info();
+        self.stats = PackageStats::compute(&self.packages, &self.outdated_packages);
     }
 
-    pub fn get_package_stats(&self) -> PackageStats {
-        let mut pacman_packages = Vec::new();
-        let mut aur_packages = Vec::new();
-        let mut apt_packages = Vec::new();
-        let mut dnf_packages = Vec::new();
-        let mut flatpak_packages = Vec::new();
-
-        for package in &self.packages {
-            match package.source.as_str() {
-                "pacman" => pacman_packages.push(package),
-                "aur" => aur_packages.push(package),
-                "apt" => apt_packages.push(package),
-                "dnf" => dnf_packages.push(package),
-                "flatpak" => flatpak_packages.push(package),
-                _ => {}
+    /// Replaces the error recorded for `source` and `operation` with the
+    /// outcome of `result`, returning the items (none on failure).
+    fn record<T>(
+        &mut self,
+        source: PackageManager,
+        operation: Operation,
+        result: Result<Vec<T>, FetchError>,
+    ) -> Vec<T> {
+        self.errors
+            .retain(|e| (e.source, e.operation) != (source, operation));
+        match result {
+            Ok(packages) => packages,
+            Err(error) => {
+                self.errors.push(SourceError::new(source, operation, error));
+                self.errors.sort_by_key(|e| (e.source, e.operation));
+                Vec::new()
             }
         }
+    }
 
-        PackageStats {
-            total: pacman_packages.len()
-                + aur_packages.len()
-                + apt_packages.len()
-                + dnf_packages.len()
-                + flatpak_packages.len(),
-            pacman: pacman_packages.len(),
-            aur: aur_packages.len(),
-            apt: apt_packages.len(),
-            dnf: dnf_packages.len(),
-            flatpak: flatpak_packages.len(),
-            outdated: self.outdated_packages.len(),
-        }
+    /// The latest [`RECENT_EVENTS`] events, newest first.
+    pub fn recent_events(&self) -> impl Iterator<Item = &PackageEvent> {
+        self.events.iter().rev().take(RECENT_EVENTS)
+    }
+
+    /// Number of problems with the given severity.
+    pub fn error_count(&self, severity: Severity) -> usize {
+        self.errors.iter().filter(|e| e.severity == severity).count()
+    }
+
+    /// The errors hit while running `operation`.
+    pub fn errors_for(&self, operation: Operation) -> impl Iterator<Item = &SourceError> {
+        self.errors.iter().filter(move |e| e.operation == operation)
     }
 }
 
-#[derive(Debug)]
-pub struct PackageStats {
-    pub total: usize,
-    pub pacman: usize,
-    pub aur: usize,
-    pub apt: usize,
-    pub dnf: usize,
-    pub flatpak: usize,
-    pub outdated: usize,
+/// Replaces the packages from `manager` in `list` with `packages`.
+///
+/// The list stays grouped by manager, whatever order the results arrive in.
+fn replace_source(list: &mut Vec<PackageInfo>, manager: PackageManager, packages: Vec<PackageInfo>) {
+    list.retain(|package| package.source != manager);
+    list.extend(packages);
+    list.sort_by_key(|package| package.source);
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
+    }
 }
@@ -0,0 +1,528 @@
+use std::error::Error;
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+
+use crate::app::{App, RECENT_EVENTS};
+use crate::fetch::events::{parse_date, EventFilter};
+use crate::fetch::stats::PackageStats;
+use crate::fetch::system::SystemField;
+use crate::json::Report;
+use crate::ui::logo::Logo;
+use crate::ui::render_ascii_ui;
+use crate::fetch::{
+    format_size, EventAction, InstallReason, PackageEvent, PackageInfo, PackageKind,
+    PackageManager, Registry, SourceError,
+};
+use crate::utils::{format_event_versions, format_timestamp, format_version, format_version_change};
+
+pub const USAGE: &str = "\
+Usage: package-fetch [COMMAND] [OPTIONS]
+
+Commands:
+  tui            Interactive dashboard (default)
+  summary        Print the one-shot fetch output
+  list           List installed packages
+  outdated       List packages with pending updates
+  recent         List recent installs, upgrades and removals
+  history        Print the package event timeline of every source
+  info <PKG>     Show details about an installed package
+  stats          Print package statistics
+
+Options:
+  -s, --source <NAME>   Only query these sources (pacman, aur, apt, dnf, flatpak);
+                        repeat or separate with commas
+  -n, --limit <N>       Print at most N packages
+  -e, --explicit-only   Skip packages installed as dependencies
+  -a, --action <NAME>   Only print these history events (installed, upgraded,
+                        downgraded, reinstalled, removed); repeat or separate
+                        with commas
+      --since <DATE>    Only print history from this day on (YYYY-MM-DD)
+      --until <DATE>    Only print history up to and including this day
+  -f, --fetch           Print the one-shot fetch output instead of the TUI;
+                        implied when stdout is not a terminal
+  -j, --json            Print JSON instead of text (see the README for the schema)
+      --logo <LOGO>     Use a built-in logo by distro ID (arch, debian, ubuntu,
+                        fedora, ...) or load one from a text file
+      --no-logo         Do not draw a logo
+      --hide <FIELDS>   Hide system info rows: os, kernel, hostname, uptime, cpu,
+                        memory, swap, load, shell, terminal, de, init
+  -h, --help            Print this help
+  -V, --version         Print the version";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Tui,
+    Summary,
+    List,
+    Outdated,
+    Recent,
+    History,
+    Info(String),
+    Stats,
+    Help,
+    Version,
+}
+
+/// Options that narrow down which packages are printed.
+#[derive(Debug, Clone, Default)]
+pub struct Filters {
+    pub sources: Vec<PackageManager>, // empty means all sources
+    pub limit: Option<usize>,
+    pub explicit_only: bool,
+    pub events: EventFilter, // for `history`; sources are filtered by the registry
+}
+
+#[derive(Debug, Clone)]
+pub struct Cli {
+    pub command: Command,
+    pub filters: Filters,
+    pub json: bool,
+    pub hidden_fields: Vec<SystemField>,
+    pub logo: LogoChoice,
+}
+
+/// Which logo to draw.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum LogoChoice {
+    #[default]
+    Distro,
+    None,
+    /// A built-in logo name or a path to a logo file.
+    Custom(String),
+}
+
+impl Cli {
+    /// Parses the arguments following the program name.
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut command = None;
+        let mut fetch = false;
+        let mut json = false;
+        let mut hidden_fields = Vec::new();
+        let mut logo = LogoChoice::Distro;
+        let mut filters = Filters::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            // Accept both `--limit 5` and `--limit=5`
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => {
+                    (flag.to_string(), Some(value.to_string()))
+                }
+                _ => (arg.clone(), None),
+            };
+            let mut value = |name: &str| {
+                inline
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("{} requires a value", name))
+            };
+
+            match flag.as_str() {
+                "-h" | "--help" => return Ok(Self::with(Command::Help)),
+                "-V" | "--version" => return Ok(Self::with(Command::Version)),
+                "-s" | "--source" => {
+                    for id in value(&flag)?.split(',') {
+                        let manager = PackageManager::from_id(id.trim())
+                            .ok_or_else(|| format!("unknown source '{}'", id))?;
+                        filters.sources.push(manager);
+                    }
+                }
+                "-n" | "--limit" => {
+                    let limit = value(&flag)?;
+                    filters.limit = Some(
+                        limit
+                            .parse()
+                            .map_err(|_| format!("invalid limit '{}'", limit))?,
+                    );
+                }
+                "-e" | "--explicit-only" => filters.explicit_only = true,
+                "-a" | "--action" => {
+                    for id in value(&flag)?.split(',') {
+                        let action = EventAction::from_id(id.trim())
+                            .ok_or_else(|| format!("unknown action '{}'", id))?;
+                        filters.events.actions.push(action);
+                    }
+                }
+                "--since" => {
+                    let date = value(&flag)?;
+                    filters.events.since =
+                        Some(parse_date(&date).ok_or_else(|| format!("invalid date '{}'", date))?);
+                }
+                "--until" => {
+                    let date = value(&flag)?;
+                    let day = parse_date(&date).ok_or_else(|| format!("invalid date '{}'", date))?;
+                    filters.events.until = Some(day + 86_400);
+                }
+                "-f" | "--fetch" => fetch = true,
+                "-j" | "--json" => json = true,
+                "--logo" => logo = LogoChoice::Custom(value(&flag)?),
+                "--no-logo" => logo = LogoChoice::None,
+                "--hide" => {
+                    for id in value(&flag)?.split(',') {
+                        let field = SystemField::from_id(id.trim())
+                            .ok_or_else(|| format!("unknown system field '{}'", id))?;
+                        hidden_fields.push(field);
+                    }
+                }
+                _ if flag.starts_with('-') => return Err(format!("unknown option '{}'", arg)),
+                _ if command.is_some() => return Err(format!("unexpected argument '{}'", arg)),
+                "tui" => command = Some(Command::Tui),
+                "summary" => command = Some(Command::Summary),
+                "list" => command = Some(Command::List),
+                "outdated" => command = Some(Command::Outdated),
+                "recent" => command = Some(Command::Recent),
+                "history" => command = Some(Command::History),
+                "stats" => command = Some(Command::Stats),
+                "info" => {
+                    let name = args.next().ok_or("info requires a package name")?;
+                    command = Some(Command::Info(name));
+                }
+                _ => return Err(format!("unknown command '{}'", arg)),
+            }
+        }
+
+        // Without a command, fall back to the fetch output where the
+        // alternate screen is unwanted or impossible
+        let command = command.unwrap_or(if fetch || json || !io::stdout().is_terminal() {
+            Command::Summary
+        } else {
+            Command::Tui
+        });
+
+        Ok(Self {
+            command,
+            filters,
+            json,
+            hidden_fields,
+            logo,
+        })
+    }
+
+    fn with(command: Command) -> Self {
+        Self {
+            command,
+            filters: Filters::default(),
+            json: false,
+            hidden_fields: Vec::new(),
+            logo: LogoChoice::Distro,
+        }
+    }
+
+    /// Builds the default registry restricted to the `--source` filter.
+    pub fn registry(&self) -> Registry {
+        let mut registry = Registry::default();
+        if !self.filters.sources.is_empty() {
+            registry.retain(|source| self.filters.sources.contains(&source.manager()));
+        }
+        registry
+    }
+
+    /// Builds the app for the selected sources, system fields and logo.
+    ///
+    /// Collection runs in the background; call [`App::wait`] for the full
+    /// state.
+    pub fn app(&self) -> Result<App, Box<dyn Error>> {
+        // Load a custom logo first so a bad path fails before collection starts
+        let custom_logo = match &self.logo {
+            LogoChoice::Custom(name) => Some(match Logo::builtin(name) {
+                Some(logo) => logo,
+                None => Logo::from_file(Path::new(name))
+                    .map_err(|e| format!("cannot read logo '{}': {}", name, e))?,
+            }),
+            _ => None,
+        };
+
+        let mut app = App::start(self.registry());
+        app.system_fields
+            .retain(|field| !self.hidden_fields.contains(field));
+        match self.logo {
+            LogoChoice::Distro => {}
+            LogoChoice::None => app.logo = None,
+            LogoChoice::Custom(_) => app.logo = custom_logo,
+        }
+        Ok(app)
+    }
+}
+
+impl Filters {
+    /// Applies `--explicit-only` and `--limit` to a package list.
+    pub fn apply(&self, mut packages: Vec<PackageInfo>) -> Vec<PackageInfo> {
+        if self.explicit_only {
+            packages.retain(|p| p.reason != Some(InstallReason::Dependency));
+        }
+        if let Some(limit) = self.limit {
+            packages.truncate(limit);
+        }
+        packages
+    }
+}
+
+/// Runs a non-interactive command, printing to stdout.
+pub fn run(cli: &Cli) -> Result<(), Box<dyn Error>> {
+    let registry = cli.registry();
+
+    match &cli.command {
+        Command::Tui => unreachable!("the TUI is started by main"),
+        Command::Help => println!("{}", USAGE),
+        Command::Version => println!("package-fetch {}", env!("CARGO_PKG_VERSION")),
+        Command::Summary => {
+            let mut app = cli.app()?;
+            app.wait();
+            if cli.json {
+                print_json(&Report::from_app(&app))?;
+            } else {
+                render_ascii_ui(&app);
+            }
+        }
+        Command::List => {
+            let (packages, errors) = registry.installed();
+            let packages = cli.filters.apply(packages);
+            if cli.json {
+                return print_json(&Report {
+                    packages: Some(&packages),
+                    errors: Some(&errors),
+                    ..Report::empty()
+                });
+            }
+            print_warnings(&errors);
+            for package in packages {
+                println!("{} {} ({})", package.name, format_version(&package.version), package.source);
+            }
+        }
+        Command::Outdated => {
+            let (outdated, errors) = registry.outdated();
+            let outdated = cli.filters.apply(outdated);
+            if cli.json {
+                return print_json(&Report {
+                    outdated_packages: Some(&outdated),
+                    errors: Some(&errors),
+                    ..Report::empty()
+                });
+            }
+            print_warnings(&errors);
+            for package in outdated {
+                match &package.repo {
+                    Some(repo) => println!(
+                        "{} {} ({}, {})",
+                        package.name,
+                        format_version_change(&package),
+                        package.source,
+                        repo
+                    ),
+                    None => println!("{} {} ({})", package.name, format_version_change(&package), package.source),
+                }
+            }
+        }
+        Command::Recent => {
+            let (events, errors) = registry.events();
+            let limit = cli.filters.limit.unwrap_or(RECENT_EVENTS);
+            let recent: Vec<&PackageEvent> = events.iter().rev().take(limit).collect();
+            if cli.json {
+                return print_json(&Report {
+                    recent_events: Some(recent),
+                    errors: Some(&errors),
+                    ..Report::empty()
+                });
+            }
+            print_warnings(&errors);
+            print_events(&recent);
+        }
+        Command::History => {
+            let (events, errors) = registry.events();
+            let mut timeline: Vec<&PackageEvent> = events
+                .iter()
+                .filter(|event| cli.filters.events.matches(event))
+                .collect();
+            // Keep the latest events when limited
+            if let Some(limit) = cli.filters.limit {
+                timeline.drain(..timeline.len().saturating_sub(limit));
+            }
+            if cli.json {
+                return print_json(&Report {
+                    events: Some(timeline),
+                    errors: Some(&errors),
+                    ..Report::empty()
+                });
+            }
+            print_warnings(&errors);
+            print_events(&timeline);
+        }
+        Command::Info(name) => {
+            let (mut packages, errors) = registry.installed();
+            // Filter by name first, so --limit cannot cut the package off
+            packages.retain(|p| p.name == *name);
+            let packages = cli.filters.apply(packages);
+            print_warnings(&errors);
+            if packages.is_empty() {
+                return Err(format!("package '{}' was not found", name).into());
+            }
+            if cli.json {
+                return print_json(&Report {
+                    packages: Some(&packages),
+                    errors: Some(&errors),
+                    ..Report::empty()
+                });
+            }
+            for (i, package) in packages.iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                print_info(package);
+            }
+        }
+        Command::Stats => {
+            let (packages, mut errors) = registry.installed();
+            let (outdated, outdated_errors) = registry.outdated();
+            errors.extend(outdated_errors);
+            let stats = PackageStats::compute(&packages, &outdated);
+            if cli.json {
+                return print_json(&Report {
+                    stats: Some(&stats),
+                    errors: Some(&errors),
+                    ..Report::empty()
+                });
+            }
+            print_warnings(&errors);
+            print_stats(&stats);
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints events in the given order, putting the events of one
+/// transaction under its command line.
+fn print_events(events: &[&PackageEvent]) {
+    let mut previous: Option<&PackageEvent> = None;
+    for event in events.iter().copied() {
+        let grouped = event.transaction.is_some() && event.command.is_some();
+        if grouped && !previous.is_some_and(|p| p.same_transaction(event)) {
+            println!(
+                "{} {} ({})",
+                format_timestamp(event.timestamp),
+                event.command.as_deref().unwrap_or_default(),
+                event.source
+            );
+        }
+        let mut change = format!("{} {}", event.action, event.name);
+        let versions = format_event_versions(event);
+        if !versions.is_empty() {
+            change.push(' ');
+            change.push_str(&versions);
+        }
+        if grouped {
+            println!("  {}", change);
+        } else {
+            println!("{} {} ({})", format_timestamp(event.timestamp), change, event.source);
+        }
+        previous = Some(event);
+    }
+}
+
+/// Reports source failures on stderr, keeping stdout parseable.
+fn print_warnings(errors: &[SourceError]) {
+    for error in errors {
+        eprintln!("{}: {}", error.severity.as_str(), error);
+    }
+}
+
+fn print_json(report: &Report) -> Result<(), Box<dyn Error>> {
+    let json = report.to_string_pretty()?;
+    match writeln!(io::stdout().lock(), "{}", json) {
+        // The reader went away, e.g. `package-fetch --json | head`
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        result => Ok(result?),
+    }
+}
+
+fn print_info(package: &PackageInfo) {
+    let field = |label: &str, value: &str| println!("{:<15}: {}", label, value);
+
+    field("Name", &package.name);
+    field("Version", &format_version(&package.version));
+    field("Source", package.source.display_name());
+    if let Some(v) = &package.available_version {
+        field("Available", v);
+    }
+    if let Some(epoch) = package.epoch {
+        field("Epoch", &epoch.to_string());
+    }
+    if let Some(release) = &package.release {
+        field("Release", release);
+    }
+    if let Some(description) = &package.description {
+        field("Description", description);
+    }
+    if let Some(arch) = &package.arch {
+        field("Architecture", arch);
+    }
+    if let Some(repo) = &package.repo {
+        field("Repository", repo);
+    }
+    if let Some(kind) = package.kind {
+        let kind = match kind {
+            PackageKind::Application => "application",
+            PackageKind::Runtime if package.unused => "runtime (unused)",
+            PackageKind::Runtime => "runtime",
+        };
+        field("Kind", kind);
+    }
+    if let Some(branch) = &package.branch {
+        field("Branch", branch);
+    }
+    if let Some(scope) = package.scope {
+        field("Installation", scope.as_str());
+    }
+    if let Some(section) = &package.section {
+        field("Section", section);
+    }
+    if let Some(priority) = &package.priority {
+        field("Priority", priority);
+    }
+    if package.essential {
+        field("Essential", "yes");
+    }
+    if !package.licenses.is_empty() {
+        field("Licenses", &package.licenses.join(", "));
+    }
+    if let Some(size) = package.size {
+        field("Installed Size", &format_size(size));
+    }
+    if let Some(packager) = &package.packager {
+        field("Packager", packager);
+    }
+    if let Some(date) = package.install_date {
+        field("Install Date", &format_timestamp(date));
+    }
+    if let Some(reason) = package.reason {
+        field(
+            "Install Reason",
+            match reason {
+                InstallReason::Explicit => "Explicitly installed",
+                InstallReason::Dependency => "Installed as a dependency",
+            },
+        );
+    }
+}
+
+fn print_stats(stats: &PackageStats) {
+    println!("Total: {}", stats.total);
+    for (manager, count) in &stats.by_source {
+        println!(
+            "{}: {} ({} outdated)",
+            manager.display_name(),
+            count,
+            stats.outdated_count(*manager)
+        );
+    }
+    println!("Outdated: {}", stats.outdated);
+    println!("Explicit: {}", stats.explicit);
+    println!("Dependencies: {}", stats.dependency);
+    println!("Installed size: {}", format_size(stats.installed_size));
+    if stats.applications + stats.runtimes > 0 {
+        println!(
+            "Flatpak: {} applications, {} runtimes ({} unused)",
+            stats.applications, stats.runtimes, stats.unused_runtimes
+        );
+    }
+}
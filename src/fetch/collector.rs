@@ -0,0 +1,135 @@
+//! Background data collection.
+//!
+//! Every package source runs on its own worker thread, alongside one thread
+//! for disks and one for system info. Results are sent back over a channel as
+//! they become available, so slow sources (network-bound update checks) do not
+//! hold up the fast ones.
+
+use std::collections::BTreeMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use crate::fetch::system::{SystemInfo, SystemReader};
+use crate::fetch::{
+    get_disk_info, DiskInfo, FetchError, PackageEvent, PackageInfo, PackageManager, Registry,
+};
+
+/// A screen area whose data is collected in the background.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Panel {
+    Packages,
+    Outdated,
+    Recent,
+    Disks,
+    System,
+}
+
+/// One result sent by a worker.
+///
+/// Package results are per source and replace whatever that source
+/// reported before.
+pub enum Update {
+    Detected(PackageManager, bool),
+    Installed(PackageManager, Result<Vec<PackageInfo>, FetchError>),
+    Outdated(PackageManager, Result<Vec<PackageInfo>, FetchError>),
+    Events(PackageManager, Result<Vec<PackageEvent>, FetchError>),
+    Disks(Vec<DiskInfo>),
+    System(Box<SystemInfo>),
+}
+
+impl Update {
+    /// The panel this result belongs to, if any.
+    pub fn panel(&self) -> Option<Panel> {
+        match self {
+            Update::Detected(..) => None,
+            Update::Installed(..) => Some(Panel::Packages),
+            Update::Outdated(..) => Some(Panel::Outdated),
+            Update::Events(..) => Some(Panel::Recent),
+            Update::Disks(_) => Some(Panel::Disks),
+            Update::System(_) => Some(Panel::System),
+        }
+    }
+}
+
+/// A running collection.
+pub struct Collection {
+    receiver: Receiver<Update>,
+    pending: BTreeMap<Panel, usize>, // results still expected per panel
+}
+
+impl Collection {
+    /// Starts collecting from every source in `registry`.
+    pub fn start(registry: &Registry) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let mut pending = BTreeMap::new();
+
+        for source in registry.shared() {
+            for panel in [Panel::Packages, Panel::Recent, Panel::Outdated] {
+                *pending.entry(panel).or_insert(0) += 1;
+            }
+            let sender = sender.clone();
+            thread::spawn(move || {
+                let manager = source.manager();
+                let detected = source.detect();
+                let _ = sender.send(Update::Detected(manager, detected));
+                if !detected {
+                    // Still report, so the panels stop waiting for this source
+                    let _ = sender.send(Update::Installed(manager, Ok(Vec::new())));
+                    let _ = sender.send(Update::Events(manager, Ok(Vec::new())));
+                    let _ = sender.send(Update::Outdated(manager, Ok(Vec::new())));
+                    return;
+                }
+                // Cheapest first; outdated checks may hit the network
+                let _ = sender.send(Update::Installed(manager, source.installed()));
+                let _ = sender.send(Update::Events(manager, source.events()));
+                let _ = sender.send(Update::Outdated(manager, source.outdated()));
+            });
+        }
+
+        pending.insert(Panel::Disks, 1);
+        spawn(&sender, || Update::Disks(get_disk_info()));
+        pending.insert(Panel::System, 1);
+        spawn(&sender, || Update::System(Box::new(SystemReader::default().system_info())));
+
+        Self { receiver, pending }
+    }
+
+    /// Returns the next result without blocking.
+    pub fn try_recv(&mut self) -> Option<Update> {
+        let update = self.receiver.try_recv().ok()?;
+        self.received(&update);
+        Some(update)
+    }
+
+    /// Blocks until the next result arrives, or returns `None` once every
+    /// worker has finished.
+    pub fn recv(&mut self) -> Option<Update> {
+        let update = self.receiver.recv().ok()?;
+        self.received(&update);
+        Some(update)
+    }
+
+    pub fn is_loading(&self, panel: Panel) -> bool {
+        self.pending.get(&panel).is_some_and(|n| *n > 0)
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.pending.values().all(|n| *n == 0)
+    }
+
+    fn received(&mut self, update: &Update) {
+        if let Some(n) = update.panel().and_then(|panel| self.pending.get_mut(&panel)) {
+            *n = n.saturating_sub(1);
+        }
+    }
+}
+
+fn spawn<F>(sender: &Sender<Update>, collect: F)
+where
+    F: FnOnce() -> Update + Send + 'static,
+{
+    let sender = sender.clone();
+    thread::spawn(move || {
+        let _ = sender.send(collect());
+    });
+}
@@ -1,91 +1,160 @@
-use std::process::Command;
+use std::ffi::CString;
+use std::fs;
+use std::io;
+use std::mem::MaybeUninit;
 
-#[derive(Debug, Clone)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiskInfo {
     pub device: String,
     pub mount_point: String,
-    pub total: u64,
+    pub fs_type: String,
+    pub options: Vec<String>,
+    pub total: u64, // bytes
     pub used: u64,
-    pub available: u64,
+    pub available: u64, // bytes available to unprivileged users
     pub usage_percentage: f64,
+    pub inodes_total: u64,
+    pub inodes_used: u64,
+    pub inodes_free: u64,
+}
+
+/// One line of `/proc/self/mounts`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountEntry {
+    pub device: String,
+    pub mount_point: String,
+    pub fs_type: String,
+    pub options: Vec<String>,
 }
 
 pub fn get_disk_info() -> Vec<DiskInfo> {
-    let mut disks = Vec::new();
-    
-    // Get disk usage using df command
-    let output = Command::new("df")
-        .arg("-h")
-        .arg("--output=source,target,size,used,avail,pcent")
-        .output();
-    
-    match output {
-        Ok(result) if result.status.success() => {
-            let output_str = String::from_utf8_lossy(&result.stdout);
-            let lines: Vec<&str> = output_str.lines().collect();
-            
-            // Skip header line
-            for line in lines.iter().skip(1) {
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() >= 6 {
-                    let device = parts[0].to_string();
-                    let mount_point = parts[1].to_string();
-                    
-                    // Skip special filesystems
-                    if device.starts_with("/dev/") && !device.contains("loop") {
-                        if let (Ok(total), Ok(used), Ok(available)) = (
-                            parse_size(parts[2]),
-                            parse_size(parts[3]),
-                            parse_size(parts[4])
-                        ) {
-                            let usage_percentage = if total > 0 {
-                                (used as f64 / total as f64) * 100.0
-                            } else {
-                                0.0
-                            };
-                            
-                            disks.push(DiskInfo {
-                                device,
-                                mount_point,
-                                total,
-                                used,
-                                available,
-                                usage_percentage,
-                            });
-                        }
-                    }
-                }
+    let Ok(mounts) = fs::read_to_string("/proc/self/mounts") else {
+        return Vec::new();
+    };
+
+    let mut disks: Vec<DiskInfo> = disk_mounts(&parse_mounts(&mounts))
+        .into_iter()
+        .filter_map(|mount| statvfs_disk(mount).ok())
+        .collect();
+
+    // Sort by usage percentage (highest first)
+    disks.sort_by(|a, b| b.usage_percentage.total_cmp(&a.usage_percentage));
+    disks
+}
+
+/// Parses the contents of `/proc/self/mounts` (or `/proc/mounts`).
+pub fn parse_mounts(contents: &str) -> Vec<MountEntry> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            Some(MountEntry {
+                device: unescape(parts.next()?),
+                mount_point: unescape(parts.next()?),
+                fs_type: parts.next()?.to_string(),
+                options: parts
+                    .next()
+                    .unwrap_or_default()
+                    .split(',')
+                    .map(str::to_string)
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+/// Keeps the mounts of real block devices, one per device.
+///
+/// Bind mounts and btrfs subvolumes show up as several mounts of the same
+/// device; the one with the shortest mount point (usually the top-level
+/// one, e.g. `/` rather than `/home`) is kept.
+pub fn disk_mounts(mounts: &[MountEntry]) -> Vec<&MountEntry> {
+    let mut disks: Vec<&MountEntry> = Vec::new();
+
+    // Skip special filesystems
+    for mount in mounts
+        .iter()
+        .filter(|m| m.device.starts_with("/dev/") && !m.device.contains("loop"))
+    {
+        match disks.iter_mut().find(|d| d.device == mount.device) {
+            Some(existing) if mount.mount_point.len() < existing.mount_point.len() => {
+                *existing = mount
             }
-        }
-        Ok(_) => {
-            // df command executed but no output
-        }
-        Err(_) => {
-            // df command not found or failed
+            Some(_) => {}
+            None => disks.push(mount),
         }
     }
-    
-    // Sort by usage percentage (highest first)
-    disks.sort_by(|a, b| b.usage_percentage.partial_cmp(&a.usage_percentage).unwrap());
+
     disks
 }
 
-fn parse_size(size_str: &str) -> Result<u64, std::num::ParseIntError> {
-    // Parse sizes like "100G", "500M", "1T"
-    let size_str = size_str.trim();
-    let (number, unit) = if size_str.ends_with('G') {
-        (size_str.trim_end_matches('G'), 1024 * 1024 * 1024)
-    } else if size_str.ends_with('M') {
-        (size_str.trim_end_matches('M'), 1024 * 1024)
-    } else if size_str.ends_with('K') {
-        (size_str.trim_end_matches('K'), 1024)
-    } else if size_str.ends_with('T') {
-        (size_str.trim_end_matches('T'), 1024 * 1024 * 1024 * 1024)
+/// Reads exact usage numbers for a mount with `statvfs(3)`.
+fn statvfs_disk(mount: &MountEntry) -> io::Result<DiskInfo> {
+    let path = CString::new(mount.mount_point.as_str())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let mut stat = MaybeUninit::<libc::statvfs>::uninit();
+
+    // SAFETY: `path` is a valid NUL-terminated string and `stat` points to
+    // enough memory for a `statvfs` struct, which is initialised on success.
+    let stat = unsafe {
+        if libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        stat.assume_init()
+    };
+
+    let block_size = stat.f_frsize as u64;
+    let total = stat.f_blocks as u64 * block_size;
+    let used = (stat.f_blocks as u64).saturating_sub(stat.f_bfree as u64) * block_size;
+    let available = stat.f_bavail as u64 * block_size;
+
+    // Same formula as df: blocks reserved for root count as neither used
+    // nor available
+    let usage_percentage = if used + available > 0 {
+        (used as f64 / (used + available) as f64) * 100.0
     } else {
-        (size_str, 1)
+        0.0
     };
-    
-    number.parse::<u64>().map(|n| n * unit)
+
+    let inodes_total = stat.f_files as u64;
+    let inodes_free = stat.f_ffree as u64;
+
+    Ok(DiskInfo {
+        device: mount.device.clone(),
+        mount_point: mount.mount_point.clone(),
+        fs_type: mount.fs_type.clone(),
+        options: mount.options.clone(),
+        total,
+        used,
+        available,
+        usage_percentage,
+        inodes_total,
+        inodes_used: inodes_total.saturating_sub(inodes_free),
+        inodes_free,
+    })
+}
+
+/// Decodes the octal escapes (`\040` for a space) the kernel uses in mount
+/// tables.
+fn unescape(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && i + 4 <= bytes.len() {
+            let digits = std::str::from_utf8(&bytes[i + 1..i + 4]).unwrap_or_default();
+            if let Ok(code) = u8::from_str_radix(digits, 8) {
+                out.push(code);
+                i += 4;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
 }
 
 pub fn format_size(bytes: u64) -> String {
@@ -103,4 +172,64 @@ pub fn format_size(bytes: u64) -> String {
     } else {
         format!("{:.1}{}", size, UNITS[unit_index])
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetch::fixture;
+
+    fn mounts() -> Vec<MountEntry> {
+        parse_mounts(&fs::read_to_string(fixture("proc").join("mounts")).unwrap())
+    }
+
+    #[test]
+    fn parse_mounts_fields() {
+        let mounts = mounts();
+        assert_eq!(mounts.len(), 13);
+
+        let root = &mounts[4];
+        assert_eq!(root.device, "/dev/nvme0n1p2");
+        assert_eq!(root.mount_point, "/");
+        assert_eq!(root.fs_type, "btrfs");
+        assert_eq!(root.options[..2], ["rw", "noatime"]);
+        assert_eq!(root.options.last().map(String::as_str), Some("subvol=/@"));
+
+        // \040 is a space
+        assert_eq!(mounts[10].mount_point, "/run/media/jane/My Backup Drive");
+    }
+
+    #[test]
+    fn one_mount_per_disk() {
+        let mounts = mounts();
+        let disks: Vec<(&str, &str)> = disk_mounts(&mounts)
+            .iter()
+            .map(|m| (m.device.as_str(), m.mount_point.as_str()))
+            .collect();
+        // btrfs subvolumes and the second mount of sda1 are folded into the
+        // shortest mount point; loop devices and virtual filesystems are left out
+        assert_eq!(
+            disks,
+            [
+                ("/dev/nvme0n1p2", "/"),
+                ("/dev/nvme0n1p1", "/boot"),
+                ("/dev/sda1", "/srv/backup"),
+            ]
+        );
+    }
+
+    #[test]
+    fn unescape_octal_sequences() {
+        assert_eq!(unescape(r"/mnt/a\040b\011c\134d"), "/mnt/a b\tc\\d");
+        // Not an escape: too short or not octal
+        assert_eq!(unescape(r"/mnt/x\04"), r"/mnt/x\04");
+        assert_eq!(unescape(r"/mnt/x\999"), r"/mnt/x\999");
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(format_size(512), "512B");
+        assert_eq!(format_size(1536), "1.5K");
+        assert_eq!(format_size(5 * 1024 * 1024 * 1024), "5.0G");
+    }
+}
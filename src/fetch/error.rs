@@ -0,0 +1,155 @@
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::fetch::PackageManager;
+
+/// Why a source could not deliver its data.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FetchError {
+    /// A helper program is not installed.
+    ToolMissing { tool: String },
+    /// A helper program ran but reported failure.
+    NonZeroExit {
+        command: String,
+        code: Option<i32>, // None if killed by a signal
+        stderr: String,
+    },
+    /// Output or a database file was not in the expected format.
+    Parse { input: String, message: String },
+    /// A file or program could not be accessed.
+    PermissionDenied { path: String },
+    /// A helper program was killed after running for too long.
+    Timeout { command: String, seconds: u64 },
+    /// Any other I/O failure.
+    Io { path: String, message: String },
+}
+
+impl FetchError {
+    /// How serious the error is.
+    ///
+    /// A missing helper only means an optional feature of that source is
+    /// unavailable (e.g. `checkupdates` from pacman-contrib), so it is a
+    /// warning; everything else is an error.
+    pub fn severity(&self) -> Severity {
+        match self {
+            FetchError::ToolMissing { .. } => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+
+    /// Classifies an I/O error hit while reading `path`.
+    pub fn io(path: impl AsRef<Path>, err: io::Error) -> Self {
+        let path = path.as_ref().display().to_string();
+        match err.kind() {
+            io::ErrorKind::PermissionDenied => FetchError::PermissionDenied { path },
+            _ => FetchError::Io {
+                path,
+                message: err.to_string(),
+            },
+        }
+    }
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::ToolMissing { tool } => write!(f, "{} is not installed", tool),
+            FetchError::NonZeroExit {
+                command,
+                code,
+                stderr,
+            } => {
+                match code {
+                    Some(code) => write!(f, "`{}` exited with status {}", command, code)?,
+                    None => write!(f, "`{}` was killed by a signal", command)?,
+                }
+                if !stderr.is_empty() {
+                    write!(f, ": {}", stderr)?;
+                }
+                Ok(())
+            }
+            FetchError::Parse { input, message } => {
+                write!(f, "cannot parse {}: {}", input, message)
+            }
+            FetchError::PermissionDenied { path } => write!(f, "permission denied: {}", path),
+            FetchError::Timeout { command, seconds } => {
+                write!(f, "`{}` timed out after {}s", command, seconds)
+            }
+            FetchError::Io { path, message } => write!(f, "{}: {}", path, message),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// What a source was asked for when it failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Operation {
+    Installed,
+    Outdated,
+    History,
+}
+
+impl Operation {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Operation::Installed => "installed",
+            Operation::Outdated => "outdated",
+            Operation::History => "history",
+        }
+    }
+}
+
+/// A [`FetchError`] together with the source and operation that hit it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceError {
+    pub source: PackageManager,
+    pub operation: Operation,
+    pub severity: Severity,
+    #[serde(flatten)]
+    pub error: FetchError,
+}
+
+impl SourceError {
+    pub fn new(source: PackageManager, operation: Operation, error: FetchError) -> Self {
+        Self {
+            source,
+            operation,
+            severity: error.severity(),
+            error,
+        }
+    }
+}
+
+impl fmt::Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}): {}",
+            self.source.display_name(),
+            self.operation.as_str(),
+            self.error
+        )
+    }
+}
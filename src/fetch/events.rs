@@ -0,0 +1,182 @@
+use std::fmt;
+use std::mem::MaybeUninit;
+
+use serde::{Deserialize, Serialize};
+use tui::style::Color;
+
+use crate::fetch::PackageManager;
+use crate::utils::days_from_civil;
+
+/// Something a package manager did to a package, read from its logs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackageEvent {
+    pub timestamp: i64, // unix timestamp
+    pub source: PackageManager,
+    pub action: EventAction,
+    pub name: String,
+    pub old_version: Option<String>, // set for upgrades, downgrades and removals
+    pub new_version: Option<String>, // set for installs, upgrades and downgrades
+    pub transaction: Option<u64>,    // shared by the events of one package manager run
+    pub command: Option<String>,     // the command line that started the transaction
+}
+
+impl PackageEvent {
+    /// Returns true if both events come from the same recorded transaction.
+    pub fn same_transaction(&self, other: &PackageEvent) -> bool {
+        self.source == other.source
+            && self.transaction.is_some()
+            && self.transaction == other.transaction
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventAction {
+    Installed,
+    Upgraded,
+    Downgraded,
+    Reinstalled,
+    Removed,
+}
+
+impl EventAction {
+    pub const ALL: [EventAction; 5] = [
+        EventAction::Installed,
+        EventAction::Upgraded,
+        EventAction::Downgraded,
+        EventAction::Reinstalled,
+        EventAction::Removed,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EventAction::Installed => "installed",
+            EventAction::Upgraded => "upgraded",
+            EventAction::Downgraded => "downgraded",
+            EventAction::Reinstalled => "reinstalled",
+            EventAction::Removed => "removed",
+        }
+    }
+
+    /// Parses an identifier as returned by [`EventAction::as_str`].
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|action| action.as_str().eq_ignore_ascii_case(id))
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            EventAction::Installed => Color::Green,
+            EventAction::Upgraded => Color::Cyan,
+            EventAction::Downgraded => Color::Yellow,
+            EventAction::Reinstalled => Color::Blue,
+            EventAction::Removed => Color::Red,
+        }
+    }
+}
+
+impl fmt::Display for EventAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Which events to show. Empty lists and unset bounds let everything
+/// through.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventFilter {
+    pub actions: Vec<EventAction>,
+    pub sources: Vec<PackageManager>,
+    pub since: Option<i64>, // inclusive
+    pub until: Option<i64>, // exclusive
+}
+
+impl EventFilter {
+    pub fn matches(&self, event: &PackageEvent) -> bool {
+        (self.actions.is_empty() || self.actions.contains(&event.action))
+            && (self.sources.is_empty() || self.sources.contains(&event.source))
+            && self.since.is_none_or(|since| event.timestamp >= since)
+            && self.until.is_none_or(|until| event.timestamp < until)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Parses an English month abbreviation (`Jan` to `Dec`) into its number.
+pub fn parse_month(name: &str) -> Option<u32> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    MONTHS.iter().position(|m| *m == name).map(|i| i as u32 + 1)
+}
+
+/// Parses a `YYYY-MM-DD` date into the timestamp of its midnight in UTC,
+/// the time zone dates are printed in.
+pub fn parse_date(text: &str) -> Option<i64> {
+    let text = text.trim();
+    if text.len() != 10 {
+        return None;
+    }
+    parse_datetime(&format!("{} 00:00Z", text))
+}
+
+/// Parses a `YYYY-MM-DD[T ]HH:MM[:SS][±HHMM]` date and time into a unix
+/// timestamp. Times without an offset are in the local time zone.
+pub fn parse_datetime(text: &str) -> Option<i64> {
+    let text = text.trim();
+    let date = text.get(..10)?;
+    let mut fields = date.split('-');
+    let year: i64 = fields.next()?.parse().ok()?;
+    let month: u32 = fields.next()?.parse().ok()?;
+    let day: u32 = fields.next()?.parse().ok()?;
+
+    let rest = text.get(10..)?.trim_start_matches(['T', ' ']);
+    let (time, offset) = match rest.find(['+', '-', 'Z']) {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, ""),
+    };
+    let mut fields = time.split(':');
+    let hour: i64 = fields.next()?.parse().ok()?;
+    let minute: i64 = fields.next()?.parse().ok()?;
+    let second: i64 = fields.next().map_or(Some(0), |s| s.parse().ok())?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 {
+        return None;
+    }
+
+    let days = days_from_civil(year, month, day);
+    let time = days * 86_400 + hour * 3600 + minute * 60 + second;
+    let offset = match offset.as_bytes().first() {
+        Some(sign @ (b'+' | b'-')) => {
+            let digits = offset[1..].replace(':', "");
+            let hours: i64 = digits.get(..2)?.parse().ok()?;
+            let minutes: i64 = digits.get(2..4).map_or(Some(0), |m| m.parse().ok())?;
+            let offset = hours * 3600 + minutes * 60;
+            if *sign == b'-' {
+                -offset
+            } else {
+                offset
+            }
+        }
+        Some(_) => 0, // Z
+        None => local_offset(time),
+    };
+    Some(time - offset)
+}
+
+/// The local time zone's offset from UTC in seconds at `timestamp`.
+fn local_offset(timestamp: i64) -> i64 {
+    let time = timestamp as libc::time_t;
+    let mut tm = MaybeUninit::<libc::tm>::uninit();
+    // SAFETY: `tm` points to enough memory for a `tm` struct, which is
+    // initialised on success.
+    let tm = unsafe {
+        if libc::localtime_r(&time, tm.as_mut_ptr()).is_null() {
+            return 0;
+        }
+        tm.assume_init()
+    };
+    tm.tm_gmtoff
+}
@@ -0,0 +1,194 @@
+use crate::fetch::{EventAction, PackageEvent};
+use crate::utils::{civil_from_days, days_from_civil};
+
+/// The length of one step of the growth graph. Steps start at midnight
+/// UTC; weeks start on Monday.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Period {
+    Day,
+    #[default]
+    Week,
+    Month,
+}
+
+impl Period {
+    pub const ALL: [Period; 3] = [Period::Day, Period::Week, Period::Month];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Period::Day => "day",
+            Period::Week => "week",
+            Period::Month => "month",
+        }
+    }
+
+    /// The next period in [`Period::ALL`], wrapping around.
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|p| *p == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    /// Number of the step containing `timestamp`, counted from the epoch.
+    fn step(self, timestamp: i64) -> i64 {
+        let days = timestamp.div_euclid(86_400);
+        match self {
+            Period::Day => days,
+            // 1970-01-01 was a Thursday
+            Period::Week => (days + 3).div_euclid(7),
+            Period::Month => {
+                let (year, month, _) = civil_from_days(days);
+                year * 12 + i64::from(month) - 1
+            }
+        }
+    }
+
+    /// Timestamp at which step `step` begins.
+    fn start(self, step: i64) -> i64 {
+        let days = match self {
+            Period::Day => step,
+            Period::Week => step * 7 - 3,
+            Period::Month => days_from_civil(step.div_euclid(12), step.rem_euclid(12) as u32 + 1, 1),
+        };
+        days * 86_400
+    }
+}
+
+/// The number of installed packages at the end of one step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GrowthPoint {
+    pub start: i64, // unix timestamp of the step's beginning
+    pub packages: u64,
+}
+
+/// Replays install and removal events into package counts per step, from
+/// the step of the oldest event up to the one containing `now`.
+///
+/// The logs rarely go back to the installation of the system, so the
+/// replay runs backwards from `installed_now`, today's package count:
+/// undoing every later install and removal gives the count at the end of
+/// each earlier step. Sources without a history stay constant.
+pub fn replay(events: &[PackageEvent], installed_now: usize, period: Period, now: i64) -> Vec<GrowthPoint> {
+    let Some(first) = events.iter().map(|e| e.timestamp).min() else {
+        return Vec::new();
+    };
+    let first = period.step(first);
+    let last = period.step(now).max(first);
+
+    let mut changes = vec![0i64; (last - first + 1) as usize];
+    for event in events {
+        let delta = match event.action {
+            EventAction::Installed => 1,
+            EventAction::Removed => -1,
+            _ => continue,
+        };
+        let step = period.step(event.timestamp).min(last);
+        changes[(step - first) as usize] += delta;
+    }
+
+    let mut packages = installed_now as i64;
+    let mut points = Vec::with_capacity(changes.len());
+    for (i, change) in changes.iter().enumerate().rev() {
+        points.push(GrowthPoint {
+            start: period.start(first + i as i64),
+            packages: packages.max(0) as u64,
+        });
+        packages -= change;
+    }
+    points.reverse();
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetch::PackageManager;
+
+    /// Timestamp of `hour`:00 UTC on the given day.
+    fn at(year: i64, month: u32, day: u32, hour: i64) -> i64 {
+        days_from_civil(year, month, day) * 86_400 + hour * 3600
+    }
+
+    fn event(timestamp: i64, action: EventAction) -> PackageEvent {
+        PackageEvent {
+            timestamp,
+            source: PackageManager::Pacman,
+            action,
+            name: "foo".into(),
+            old_version: None,
+            new_version: None,
+            transaction: None,
+            command: None,
+        }
+    }
+
+    fn counts(points: &[GrowthPoint]) -> Vec<(i64, u64)> {
+        points.iter().map(|p| (p.start, p.packages)).collect()
+    }
+
+    #[test]
+    fn weeks_start_on_monday() {
+        // 2024-01-01 was a Monday
+        let monday = Period::Week.step(at(2024, 1, 1, 0));
+        assert_eq!(Period::Week.start(monday), at(2024, 1, 1, 0));
+        assert_eq!(Period::Week.step(at(2024, 1, 7, 23)), monday);
+        assert_eq!(Period::Week.step(at(2023, 12, 31, 23)), monday - 1);
+        // The week of the epoch began on Monday 1969-12-29
+        assert_eq!(Period::Week.step(0), 0);
+        assert_eq!(Period::Week.start(0), at(1969, 12, 29, 0));
+        assert_eq!(Period::Week.step(at(1969, 12, 28, 12)), -1);
+    }
+
+    #[test]
+    fn month_boundaries() {
+        let february = Period::Month.step(at(2024, 2, 29, 23));
+        assert_eq!(february, 2024 * 12 + 1);
+        assert_eq!(Period::Month.start(february), at(2024, 2, 1, 0));
+        assert_eq!(Period::Month.step(at(2024, 3, 1, 0)), february + 1);
+        assert_eq!(Period::Month.step(at(2023, 12, 31, 23)), 2023 * 12 + 11);
+        assert_eq!(Period::Month.start(2024 * 12), at(2024, 1, 1, 0));
+        // One second before the epoch
+        assert_eq!(Period::Month.start(Period::Month.step(-1)), at(1969, 12, 1, 0));
+    }
+
+    #[test]
+    fn replay_backwards_from_the_current_count() {
+        let events = [
+            event(at(2024, 3, 8, 10), EventAction::Installed),
+            event(at(2024, 3, 8, 11), EventAction::Installed),
+            event(at(2024, 3, 9, 9), EventAction::Removed),
+            event(at(2024, 3, 9, 10), EventAction::Upgraded),
+            // After now, counted in the last step
+            event(at(2024, 3, 12, 0), EventAction::Installed),
+        ];
+        let points = replay(&events, 5, Period::Day, at(2024, 3, 10, 12));
+        // 5 at the end; undo the future install, the removal, then both installs
+        assert_eq!(
+            counts(&points),
+            [(at(2024, 3, 8, 0), 5), (at(2024, 3, 9, 0), 4), (at(2024, 3, 10, 0), 5)]
+        );
+    }
+
+    #[test]
+    fn replay_by_month() {
+        let events = [
+            event(at(2024, 1, 31, 23), EventAction::Installed),
+            event(at(2024, 2, 1, 0), EventAction::Installed),
+        ];
+        let points = replay(&events, 10, Period::Month, at(2024, 3, 15, 0));
+        assert_eq!(
+            counts(&points),
+            [(at(2024, 1, 1, 0), 9), (at(2024, 2, 1, 0), 10), (at(2024, 3, 1, 0), 10)]
+        );
+    }
+
+    #[test]
+    fn replay_clamps_counts_at_zero() {
+        let events = [
+            event(at(2024, 3, 8, 0), EventAction::Installed),
+            event(at(2024, 3, 9, 0), EventAction::Installed),
+        ];
+        let points = replay(&events, 1, Period::Day, at(2024, 3, 9, 12));
+        assert_eq!(counts(&points), [(at(2024, 3, 8, 0), 0), (at(2024, 3, 9, 0), 1)]);
+        assert!(replay(&[], 3, Period::Week, 0).is_empty());
+    }
+}
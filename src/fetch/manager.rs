@@ -0,0 +1,83 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use tui::style::Color;
+
+/// The package manager a package belongs to.
+///
+/// Everything the UI needs to present a source lives here, so supporting a
+/// new manager means adding a variant and filling in these tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageManager {
+    Pacman,
+    Aur,
+    Apt,
+    Dnf,
+    Flatpak,
+}
+
+impl PackageManager {
+    pub const ALL: [PackageManager; 5] = [
+        PackageManager::Pacman,
+        PackageManager::Aur,
+        PackageManager::Apt,
+        PackageManager::Dnf,
+        PackageManager::Flatpak,
+    ];
+
+    /// Short identifier, e.g. `"pacman"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PackageManager::Pacman => "pacman",
+            PackageManager::Aur => "aur",
+            PackageManager::Apt => "apt",
+            PackageManager::Dnf => "dnf",
+            PackageManager::Flatpak => "flatpak",
+        }
+    }
+
+    /// Human readable name, e.g. `"Pacman"`.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            PackageManager::Pacman => "Pacman",
+            PackageManager::Aur => "AUR",
+            PackageManager::Apt => "APT",
+            PackageManager::Dnf => "DNF",
+            PackageManager::Flatpak => "Flatpak",
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            PackageManager::Pacman => Color::Blue,
+            PackageManager::Aur => Color::Yellow,
+            PackageManager::Apt => Color::Magenta,
+            PackageManager::Dnf => Color::Red,
+            PackageManager::Flatpak => Color::Cyan,
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            PackageManager::Pacman => "▲",
+            PackageManager::Aur => "△",
+            PackageManager::Apt => "◉",
+            PackageManager::Dnf => "●",
+            PackageManager::Flatpak => "■",
+        }
+    }
+
+    /// Parses an identifier as returned by [`PackageManager::as_str`].
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|m| m.as_str().eq_ignore_ascii_case(id))
+    }
+}
+
+impl fmt::Display for PackageManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -1,6 +1,27 @@
 pub mod packages;
+pub mod manager;
+pub mod sources;
+pub mod collector;
+pub mod error;
+pub mod events;
+pub mod growth;
+pub mod profile;
 pub mod stats;
+pub mod system;
 pub mod disk_info;
 
 pub use packages::*;
-pub use disk_info::*;
\ No newline at end of file
+pub use error::{FetchError, SourceError};
+pub use events::{EventAction, PackageEvent};
+pub use manager::PackageManager;
+pub use profile::SystemProfile;
+pub use sources::{PackageSource, Registry};
+pub use disk_info::*;
+
+/// A directory under `tests/fixtures`, for tests that read files.
+#[cfg(test)]
+pub(crate) fn fixture(path: &str) -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(path)
+}
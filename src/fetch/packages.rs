@@ -1,234 +1,91 @@
-use std::process::Command;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+use crate::fetch::PackageManager;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PackageInfo {
     pub name: String,
-    pub version: String,
-    pub source: String, // "pacman", "aur", "apt", "flatpak", "dnf", "rpm"
-}
-
-pub fn detect_system() -> Vec<(&'static str, Vec<&'static str>)> {
-    if Command::new("pacman").arg("--version").output().is_ok() {
-        return vec![
-            ("pacman", vec!["-Q"]),
-            ("yay", vec!["-Qm"]), // AUR packages
-        ];
-    }
-
-    if Command::new("apt").arg("--version").output().is_ok() {
-        return vec![
-            ("dpkg", vec!["-l"]),
-            ("flatpak", vec!["list"]), // Flatpak packages
-        ];
-    }
-
-    if Command::new("dnf").arg("--version").output().is_ok() {
-        return vec![
-            ("rpm", vec!["-qa"]),      // All installed packages
-            ("flatpak", vec!["list"]), // Flatpak packages
-        ];
-    }
-
-    vec![]
+    pub version: String, // installed version
+    pub source: PackageManager,
+    pub available_version: Option<String>, // set for outdated packages
+    pub arch: Option<String>,
+    pub repo: Option<String>,
+    pub reason: Option<InstallReason>,
+    pub install_date: Option<i64>, // unix timestamp
+    pub size: Option<u64>,         // installed size in bytes
+    pub description: Option<String>,
+    pub packager: Option<String>,
+    pub licenses: Vec<String>,
+    pub section: Option<String>,
+    pub priority: Option<String>,
+    pub essential: bool,
+    pub epoch: Option<u32>,
+    pub release: Option<String>,
+    pub kind: Option<PackageKind>,
+    pub scope: Option<InstallScope>,
+    pub branch: Option<String>,
+    pub unused: bool, // a runtime nothing installed depends on
 }
 
-pub fn get_packages() -> Vec<PackageInfo> {
-    let mut packages = Vec::new();
-    let commands = detect_system();
-
-    for (command, args) in commands {
-        let output = Command::new(command).args(&args).output();
-
-        match output {
-            Ok(result) if result.status.success() => {
-                let output_str = String::from_utf8_lossy(&result.stdout);
-                let source = match command {
-                    "pacman" => "pacman",
-                    "yay" => "aur",
-                    "dpkg" => "apt",
-                    "rpm" => "dnf",
-                    "flatpak" => "flatpak",
-                    _ => "unknown",
-                };
-
-                for line in output_str.lines() {
-                    if !line.trim().is_empty() {
-                        packages.push(PackageInfo {
-                            name: line.to_string(),
-                            version: "".to_string(),
-                            source: source.to_string(),
-                        });
-                    }
-                }
-            }
-            Ok(result) => {
-                eprintln!(
-                    "Error executing {}: {}",
-                    command,
-                    String::from_utf8_lossy(&result.stderr)
-                );
-            }
-            Err(_) => {
-                // Command not found - this is normal
-            }
+impl PackageInfo {
+    /// Creates a package with only a name and source; every other field is
+    /// empty.
+    pub fn new(source: PackageManager, name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            version: String::new(),
+            source,
+            available_version: None,
+            arch: None,
+            repo: None,
+            reason: None,
+            install_date: None,
+            size: None,
+            description: None,
+            packager: None,
+            licenses: Vec::new(),
+            section: None,
+            priority: None,
+            essential: false,
+            epoch: None,
+            release: None,
+            kind: None,
+            scope: None,
+            branch: None,
+            unused: false,
         }
     }
-
-    packages
 }
 
-pub fn get_outdated_packages() -> Result<Vec<PackageInfo>, String> {
-    let mut outdated: Vec<PackageInfo> = Vec::new();
-
-    // checkupdates for pacman
-    let res = Command::new("checkupdates")
-        .output()
-        .map_err(|e| format!("checkupdates not found or failed to execute: {}", e))?;
-
-    // if checkupdates failed (non-zero exit code), return empty list
-    let out = String::from_utf8_lossy(&res.stdout);
-    for line in out.lines() {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
-        // format 
-        outdated.push(PackageInfo {
-            name: line.to_string(),
-            version: "".to_string(),
-            source: "pacman".to_string(),
-        });
-    }
-
-    // aur via yay
-    if Command::new("yay").arg("--version").output().is_ok() {
-        if let Ok(result) = Command::new("yay").arg("-Qu").output() {
-            if result.status.success() {
-                let output_str = String::from_utf8_lossy(&result.stdout);
-                for line in output_str.lines() {
-                    let line = line.trim();
-                    if line.is_empty() {
-                        continue;
-                    }
-                    outdated.push(PackageInfo {
-                        name: line.to_string(),
-                        version: "".to_string(),
-                        source: "aur".to_string(),
-                    });
-                }
-            } else {
-                eprintln!(
-                    "warn: `yay -Qu` returned non-zero: {}",
-                    String::from_utf8_lossy(&result.stderr)
-                );
-            }
-        } else {
-            eprintln!("warn: failed to run `yay -Qu`");
-        }
-    }
-
-    // fedora dnf
-    if Command::new("dnf").arg("--version").output().is_ok() {
-        if let Ok(result) = Command::new("dnf").args(["list", "upgrades"]).output() {
-            if result.status.success() {
-                let output_str = String::from_utf8_lossy(&result.stdout);
-                for line in output_str.lines().skip(1) {
-                    if !line.trim().is_empty() && !line.starts_with("Last metadata") {
-                        let parts: Vec<&str> = line.split_whitespace().collect();
-                        if parts.len() >= 2 {
-                            outdated.push(PackageInfo {
-                                name: parts[0].to_string(),
-                                version: parts[1].to_string(),
-                                source: "dnf".to_string(),
-                            });
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    Ok(outdated)
+/// What a Flatpak ref provides.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageKind {
+    Application,
+    Runtime,
 }
 
-pub fn get_recent_packages() -> Vec<PackageInfo> {
-    let mut recent = Vec::new();
-
-    // Get recently installed packages via pacman
-    if Command::new("pacman").arg("--version").output().is_ok() {
-        let output = Command::new("pacman")
-            .arg("-Q") // all installed packages
-            .output();
-
-        match output {
-            Ok(result) if result.status.success() => {
-                let output_str = String::from_utf8_lossy(&result.stdout);
-                let mut lines: Vec<&str> = output_str.lines().collect();
-
-                // Take last 5 packages
-                lines.reverse();
-                for line in lines.iter().take(5) {
-                    if !line.trim().is_empty() {
-                        recent.push(PackageInfo {
-                            name: line.to_string(),
-                            version: "".to_string(),
-                            source: "pacman".to_string(),
-                        });
-                    }
-                }
-            }
-            Ok(_) => {
-                // Command executed but no output
-            }
-            Err(_) => {
-                // Command not found
-            }
-        }
-    }
-
-    // Get recently installed packages via dnf for Fedora
-    if Command::new("dnf").arg("--version").output().is_ok() {
-        let output = Command::new("dnf")
-            .arg("history")
-            .arg("list")
-            .arg("installed")
-            .arg("--limit=5")
-            .output();
-
-        match output {
-            Ok(result) if result.status.success() => {
-                let output_str = String::from_utf8_lossy(&result.stdout);
-                for line in output_str.lines().skip(1) {
-                    // Skip header
-                    if !line.trim().is_empty() {
-                        let parts: Vec<&str> = line.split_whitespace().collect();
-                        if parts.len() >= 3 {
-                            // Extract package name from the command
-                            let command_part = parts[2..].join(" ");
-                            if command_part.contains("install") {
-                                let package_name = command_part
-                                    .split_whitespace()
-                                    .find(|s| !s.starts_with("install") && !s.starts_with("-"))
-                                    .unwrap_or("unknown");
+/// Whether a package is installed for every user or only the current one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallScope {
+    System,
+    User,
+}
 
-                                recent.push(PackageInfo {
-                                    name: package_name.to_string(),
-                                    version: "".to_string(),
-                                    source: "dnf".to_string(),
-                                });
-                            }
-                        }
-                    }
-                }
-            }
-            Ok(_) => {
-                // Command executed but no output
-            }
-            Err(_) => {
-                // Command not found
-            }
+impl InstallScope {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            InstallScope::System => "system",
+            InstallScope::User => "user",
         }
     }
+}
 
-    recent
+/// Why a package is installed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallReason {
+    Explicit,
+    Dependency,
 }
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+use crate::fetch::{PackageManager, Registry};
+
+/// The package managers found on this system.
+///
+/// Detection spawns helper programs, so it runs once per refresh and the
+/// result is cached here for the renderers.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SystemProfile {
+    pub managers: Vec<PackageManager>, // sorted, without duplicates
+}
+
+impl SystemProfile {
+    /// Runs detection for every source in `registry`.
+    pub fn detect(registry: &Registry) -> Self {
+        let mut profile = Self::default();
+        for source in registry.sources() {
+            profile.set_detected(source.manager(), source.detect());
+        }
+        profile
+    }
+
+    pub fn has(&self, manager: PackageManager) -> bool {
+        self.managers.binary_search(&manager).is_ok()
+    }
+
+    /// Records the detection result for one manager.
+    pub fn set_detected(&mut self, manager: PackageManager, detected: bool) {
+        match (self.managers.binary_search(&manager), detected) {
+            (Err(i), true) => self.managers.insert(i, manager),
+            (Ok(i), false) => {
+                self.managers.remove(i);
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,552 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::{apt_log, run_command, PackageSource};
+use crate::fetch::{FetchError, InstallReason, PackageEvent, PackageInfo, PackageManager};
+
+/// Location of the dpkg status database relative to the root.
+const STATUS_FILE: &str = "var/lib/dpkg/status";
+
+/// apt's record of automatically installed packages, relative to the root.
+const EXTENDED_STATES: &str = "var/lib/apt/extended_states";
+
+/// Downloaded repository indexes, relative to the root.
+const LISTS_DIR: &str = "var/lib/apt/lists";
+
+/// apt's own helper, used to decompress lists in whatever format apt
+/// stored them (lz4, gz, xz, zst).
+const APT_HELPER: &str = "/usr/lib/apt/apt-helper";
+
+/// Pin priority of an installed version, and of repositories marked
+/// `NotAutomatic` + `ButAutomaticUpgrades` (e.g. backports).
+const INSTALLED_PRIORITY: i32 = 100;
+
+/// Debian packages managed by apt/dpkg.
+///
+/// Installed packages come from the dpkg status database and updates from
+/// the downloaded lists, both found under `root`.
+pub struct AptSource {
+    root: PathBuf,
+}
+
+impl AptSource {
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn status_file(&self) -> PathBuf {
+        self.root.join(STATUS_FILE)
+    }
+}
+
+impl Default for AptSource {
+    fn default() -> Self {
+        Self::with_root("/")
+    }
+}
+
+impl PackageSource for AptSource {
+    fn manager(&self) -> PackageManager {
+        PackageManager::Apt
+    }
+
+    fn detect(&self) -> bool {
+        self.status_file().is_file()
+    }
+
+    fn installed(&self) -> Result<Vec<PackageInfo>, FetchError> {
+        let status_file = self.status_file();
+        let mut packages = read_status(&status_file).map_err(|e| FetchError::io(status_file, e))?;
+
+        // Packages apt did not mark as automatic were requested by the user
+        let auto = fs::read_to_string(self.root.join(EXTENDED_STATES))
+            .map(|contents| parse_auto_installed(&contents))
+            .unwrap_or_default();
+        for package in &mut packages {
+            package.reason = Some(if auto.contains(&package.name) {
+                InstallReason::Dependency
+            } else {
+                InstallReason::Explicit
+            });
+        }
+
+        Ok(packages)
+    }
+
+    /// Finds upgradable packages from the lists of the last `apt update`,
+    /// without touching the network.
+    ///
+    /// The candidate is chosen like apt does with default pinning: the
+    /// newest version from the highest-priority repository, never a
+    /// downgrade, with the installed version taking part. A package is
+    /// outdated when its candidate is newer than what is installed.
+    /// `/etc/apt/preferences` is not read.
+    fn outdated(&self) -> Result<Vec<PackageInfo>, FetchError> {
+        let status_file = self.status_file();
+        let installed = read_status(&status_file).map_err(|e| FetchError::io(status_file, e))?;
+        let versions: HashMap<(&str, &str), &str> = installed
+            .iter()
+            .map(|p| ((p.name.as_str(), p.arch.as_deref().unwrap_or("")), p.version.as_str()))
+            .collect();
+
+        let lists_dir = self.root.join(LISTS_DIR);
+        let mut lists: Vec<PathBuf> = fs::read_dir(&lists_dir)
+            .map_err(|e| FetchError::io(&lists_dir, e))?
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| is_packages_list(path))
+            .collect();
+        lists.sort();
+
+        // Best candidate per installed (name, arch): (priority, version, suite)
+        let mut candidates: HashMap<(String, String), (i32, String, Option<String>)> = HashMap::new();
+        for list in lists {
+            // Skipping a list that cannot be read would report its updates
+            // as "up to date", so it fails the check instead
+            let contents = read_list(&list)?;
+            let release = read_release(&list);
+            for fields in stanzas(&contents) {
+                let (Some(name), Some(version), Some(arch)) = (
+                    field(&fields, "Package"),
+                    field(&fields, "Version"),
+                    field(&fields, "Architecture"),
+                ) else {
+                    continue;
+                };
+                let Some(current) = versions.get(&(name, arch)) else {
+                    continue;
+                };
+                // Without a pin, apt never downgrades
+                if compare_versions(version, current) == Ordering::Less {
+                    continue;
+                }
+
+                // The installed version competes as well, at its repository's
+                // priority or INSTALLED_PRIORITY, whichever is higher
+                let (priority, best, suite) = candidates
+                    .entry((name.to_string(), arch.to_string()))
+                    .or_insert_with(|| (INSTALLED_PRIORITY, current.to_string(), None));
+                if release.priority > *priority
+                    || (release.priority == *priority && compare_versions(version, best) == Ordering::Greater)
+                {
+                    *priority = release.priority;
+                    *best = version.to_string();
+                    *suite = release.suite.clone();
+                }
+            }
+        }
+
+        let mut outdated: Vec<PackageInfo> = candidates
+            .into_iter()
+            .filter(|((name, arch), (_, version, _))| {
+                compare_versions(version, versions[&(name.as_str(), arch.as_str())]) == Ordering::Greater
+            })
+            .map(|((name, arch), (_, version, suite))| {
+                let mut package = PackageInfo::new(PackageManager::Apt, name);
+                package.version = versions[&(package.name.as_str(), arch.as_str())].to_string();
+                package.available_version = Some(version);
+                package.arch = Some(arch);
+                package.repo = suite;
+                package
+            })
+            .collect();
+        outdated.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(outdated)
+    }
+
+    /// Read from dpkg's and apt's logs, see [`apt_log::read_events`].
+    fn events(&self) -> Result<Vec<PackageEvent>, FetchError> {
+        apt_log::read_events(&self.root)
+    }
+}
+
+/// Reads the installed packages from a dpkg status file.
+pub fn read_status(path: &Path) -> io::Result<Vec<PackageInfo>> {
+    let contents = fs::read_to_string(path)?;
+    Ok(parse_status(&contents))
+}
+
+/// Parses a dpkg status file, keeping only entries whose state is
+/// `installed`, whatever the selection (`install`, `hold`, `deinstall`).
+///
+/// Removed packages whose configuration is still around (`rc` in
+/// `dpkg -l`) and half-installed ones are skipped.
+pub fn parse_status(contents: &str) -> Vec<PackageInfo> {
+    stanzas(contents)
+        .filter(|fields| {
+            field(fields, "Status").and_then(|status| status.split_whitespace().nth(2)) == Some("installed")
+        })
+        .filter_map(|fields| {
+            let name = field(&fields, "Package")?;
+            Some(PackageInfo {
+                version: field(&fields, "Version").unwrap_or_default().to_string(),
+                description: field(&fields, "Description").map(str::to_string),
+                // Installed-Size is in KiB
+                size: field(&fields, "Installed-Size")
+                    .and_then(|s| s.parse::<u64>().ok())
+                    .map(|kib| kib * 1024),
+                packager: field(&fields, "Maintainer").map(str::to_string),
+                arch: field(&fields, "Architecture").map(str::to_string),
+                section: field(&fields, "Section").map(str::to_string),
+                priority: field(&fields, "Priority").map(str::to_string),
+                essential: field(&fields, "Essential") == Some("yes"),
+                ..PackageInfo::new(PackageManager::Apt, name)
+            })
+        })
+        .collect()
+}
+
+/// Returns the names of packages marked `Auto-Installed: 1` in apt's
+/// `extended_states` file.
+pub fn parse_auto_installed(contents: &str) -> HashSet<String> {
+    stanzas(contents)
+        .filter(|fields| field(fields, "Auto-Installed") == Some("1"))
+        .filter_map(|fields| field(&fields, "Package").map(str::to_string))
+        .collect()
+}
+
+/// The compression suffix of a `*_Packages` index file: `""` for a plain
+/// list, `".lz4"`, `".gz"` and so on otherwise. `None` for other files.
+///
+/// The file name cannot tell on its own: it starts with the host, so
+/// `deb.debian.org_..._Packages` has an "extension" too.
+fn list_compression(path: &Path) -> Option<&str> {
+    let name = path.file_name()?.to_str()?;
+    let (_, suffix) = name.split_once("_Packages")?;
+    matches!(suffix, "" | ".lz4" | ".gz" | ".xz" | ".zst" | ".bz2" | ".lzma").then_some(suffix)
+}
+
+/// Returns true for `*_Packages` index files, compressed or not.
+fn is_packages_list(path: &Path) -> bool {
+    list_compression(path).is_some()
+}
+
+/// Reads a package list, decompressing it with apt-helper if needed.
+fn read_list(path: &Path) -> Result<String, FetchError> {
+    if list_compression(path).unwrap_or_default().is_empty() {
+        fs::read_to_string(path).map_err(|e| FetchError::io(path, e))
+    } else {
+        run_command(APT_HELPER, &["cat-file", &path.to_string_lossy()])
+    }
+}
+
+/// What a list's `Release` file says about its repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Release {
+    pub suite: Option<String>, // "stable", "bookworm-security", ...
+    pub priority: i32,         // default pin priority
+}
+
+impl Release {
+    /// Parses a `Release` or `InRelease` file.
+    pub fn parse(contents: &str) -> Self {
+        let mut suite = None;
+        let mut codename = None;
+        let mut not_automatic = false;
+        let mut but_automatic_upgrades = false;
+        for line in contents.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key {
+                "Suite" => suite = Some(value.to_string()),
+                "Codename" => codename = Some(value.to_string()),
+                "NotAutomatic" => not_automatic = value == "yes",
+                "ButAutomaticUpgrades" => but_automatic_upgrades = value == "yes",
+                _ => {}
+            }
+        }
+
+        let priority = match (not_automatic, but_automatic_upgrades) {
+            (false, _) => 500,
+            (true, true) => INSTALLED_PRIORITY,
+            (true, false) => 1,
+        };
+        Self {
+            suite: suite.or(codename),
+            priority,
+        }
+    }
+}
+
+/// Finds the `InRelease`/`Release` file belonging to a list.
+///
+/// `host_path_dists_SUITE_COMPONENT_binary-ARCH_Packages` belongs to
+/// `host_path_dists_SUITE_InRelease`; flat repositories are named
+/// `host_path_Packages` and `host_path_InRelease`.
+fn read_release(list: &Path) -> Release {
+    let name = list.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    let prefix = match name.find("_dists_") {
+        Some(i) => {
+            let suite_start = i + "_dists_".len();
+            let suite_end = name[suite_start..]
+                .find('_')
+                .map_or(name.len(), |j| suite_start + j);
+            &name[..suite_end]
+        }
+        None => name.split("_Packages").next().unwrap_or_default(),
+    };
+
+    ["InRelease", "Release"]
+        .iter()
+        .find_map(|file| fs::read_to_string(list.with_file_name(format!("{}_{}", prefix, file))).ok())
+        .map(|contents| Release::parse(&contents))
+        .unwrap_or(Release {
+            suite: None,
+            priority: 500,
+        })
+}
+
+/// Compares two Debian version strings (`[epoch:]upstream[-revision]`) the
+/// way dpkg does, so `1.0~rc1 < 1.0 < 1.0+b1` and `2:0.1 > 1:9`.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a_epoch, a_upstream, a_revision) = split_version(a);
+    let (b_epoch, b_upstream, b_revision) = split_version(b);
+    a_epoch
+        .cmp(&b_epoch)
+        .then_with(|| compare_fragment(a_upstream, b_upstream))
+        .then_with(|| compare_fragment(a_revision, b_revision))
+}
+
+fn split_version(version: &str) -> (u64, &str, &str) {
+    let (epoch, rest) = match version.split_once(':') {
+        Some((epoch, rest)) => (epoch.parse().unwrap_or(0), rest),
+        None => (0, version),
+    };
+    match rest.rsplit_once('-') {
+        Some((upstream, revision)) => (epoch, upstream, revision),
+        None => (epoch, rest, ""),
+    }
+}
+
+/// dpkg's `verrevcmp`: alternating runs of non-digits, compared by
+/// [`char_order`], and digits, compared numerically.
+fn compare_fragment(a: &str, b: &str) -> Ordering {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let (mut i, mut j) = (0, 0);
+
+    while i < a.len() || j < b.len() {
+        while a.get(i).is_some_and(|c| !c.is_ascii_digit())
+            || b.get(j).is_some_and(|c| !c.is_ascii_digit())
+        {
+            let (ac, bc) = (char_order(a.get(i)), char_order(b.get(j)));
+            if ac != bc {
+                return ac.cmp(&bc);
+            }
+            i += 1;
+            j += 1;
+        }
+
+        while a.get(i) == Some(&b'0') {
+            i += 1;
+        }
+        while b.get(j) == Some(&b'0') {
+            j += 1;
+        }
+        let mut first_diff = Ordering::Equal;
+        while a.get(i).is_some_and(u8::is_ascii_digit) && b.get(j).is_some_and(u8::is_ascii_digit) {
+            if first_diff == Ordering::Equal {
+                first_diff = a[i].cmp(&b[j]);
+            }
+            i += 1;
+            j += 1;
+        }
+        // The longer number is bigger
+        if a.get(i).is_some_and(u8::is_ascii_digit) {
+            return Ordering::Greater;
+        }
+        if b.get(j).is_some_and(u8::is_ascii_digit) {
+            return Ordering::Less;
+        }
+        if first_diff != Ordering::Equal {
+            return first_diff;
+        }
+    }
+    Ordering::Equal
+}
+
+/// Sort weight of a character in a non-digit run: `~` sorts before
+/// everything, even the end of the string, and letters before other
+/// symbols.
+fn char_order(c: Option<&u8>) -> i32 {
+    match c {
+        None => 0,
+        Some(b'~') => -1,
+        Some(c) if c.is_ascii_digit() => 0,
+        Some(c) if c.is_ascii_alphabetic() => *c as i32,
+        Some(c) => *c as i32 + 256,
+    }
+}
+
+/// Splits a Debian control-style file into paragraphs of `(field, value)`
+/// pairs.
+///
+/// Only the first line of each value is kept; continuation lines (starting
+/// with whitespace) are ignored.
+pub(crate) fn stanzas(contents: &str) -> impl Iterator<Item = Vec<(&str, &str)>> {
+    contents.split("\n\n").filter_map(|paragraph| {
+        let fields: Vec<(&str, &str)> = paragraph
+            .lines()
+            .filter(|line| !line.starts_with([' ', '\t']))
+            .filter_map(|line| line.split_once(':'))
+            .map(|(key, value)| (key, value.trim()))
+            .collect();
+        if fields.is_empty() {
+            None
+        } else {
+            Some(fields)
+        }
+    })
+}
+
+pub(crate) fn field<'a>(fields: &[(&str, &'a str)], name: &str) -> Option<&'a str> {
+    fields
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| *value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetch::fixture;
+
+    #[test]
+    fn parse_status_keeps_installed_packages() {
+        let packages = read_status(&fixture("apt").join(STATUS_FILE)).unwrap();
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        // Held and deinstall-selected packages are still on disk; rc and
+        // half-installed ones are not
+        assert_eq!(names, ["bash", "libc6", "linux-image-amd64", "zfsutils-linux", "nano"]);
+
+        let bash = &packages[0];
+        assert_eq!(bash.version, "5.2.15-2+b2");
+        assert_eq!(bash.size, Some(7164 * 1024));
+        assert_eq!(bash.arch.as_deref(), Some("amd64"));
+        assert_eq!(bash.section.as_deref(), Some("shells"));
+        assert_eq!(bash.priority.as_deref(), Some("required"));
+        assert_eq!(bash.description.as_deref(), Some("GNU Bourne Again SHell"));
+        assert!(bash.essential);
+        assert!(!packages[1].essential);
+    }
+
+    #[test]
+    fn parse_auto_installed_reads_marked_packages() {
+        let contents = fs::read_to_string(fixture("apt").join(EXTENDED_STATES)).unwrap();
+        let auto = parse_auto_installed(&contents);
+        assert!(auto.contains("libc6"));
+        assert!(auto.contains("vim-tiny"));
+        assert!(!auto.contains("nano"));
+        assert_eq!(auto.len(), 2);
+    }
+
+    #[test]
+    fn installed_sets_install_reasons() {
+        let source = AptSource::with_root(fixture("apt"));
+        assert!(source.detect());
+        let packages = source.installed().unwrap();
+        let reason = |name: &str| packages.iter().find(|p| p.name == name).unwrap().reason;
+        assert_eq!(reason("bash"), Some(InstallReason::Explicit));
+        assert_eq!(reason("libc6"), Some(InstallReason::Dependency));
+        assert_eq!(reason("nano"), Some(InstallReason::Explicit));
+    }
+
+    #[test]
+    fn compare_versions_like_dpkg() {
+        use Ordering::*;
+        for (a, b, expected) in [
+            // ~ sorts before everything, even the end of the version
+            ("1.0~rc1", "1.0", Less),
+            ("1.0~~", "1.0~", Less),
+            ("1.0~", "1.0", Less),
+            ("1.0", "1.0+b1", Less),
+            // Epochs win over everything else; a missing one is 0
+            ("2:0.1", "1:9.9", Greater),
+            ("0:1.0", "1.0", Equal),
+            // Revisions split at the last hyphen and compare numerically
+            ("1.0-1", "1.0-2", Less),
+            ("1.0-10", "1.0-9", Greater),
+            ("1.0", "1.0-1", Less),
+            ("1.0-0", "1.0", Equal),
+            ("1.0-beta-1", "1.0-beta-2", Less),
+            ("1.0-beta-2", "1.0-1", Greater),
+            // Letters sort before symbols, symbols by ASCII
+            ("1.0a", "1.0+", Less),
+            ("1.0a", "1.0", Greater),
+            ("1.0+1", "1.0.1", Less),
+            // Digit runs compare as numbers
+            ("1.10", "1.9", Greater),
+            ("1.001", "1.1", Equal),
+            ("5.2.15-2+b2", "5.2.15-2+b7", Less),
+            ("6.1.76-1", "6.6.13-1~bpo12+1", Less),
+        ] {
+            assert_eq!(compare_versions(a, b), expected, "{} vs {}", a, b);
+            assert_eq!(compare_versions(b, a), expected.reverse(), "{} vs {}", b, a);
+        }
+    }
+
+    #[test]
+    fn release_priorities() {
+        let stable = Release::parse("Origin: Debian\nSuite: stable\nCodename: bookworm\n");
+        assert_eq!(stable.suite.as_deref(), Some("stable"));
+        assert_eq!(stable.priority, 500);
+
+        let backports = Release::parse(
+            "Codename: bookworm-backports\nNotAutomatic: yes\nButAutomaticUpgrades: yes\n",
+        );
+        assert_eq!(backports.suite.as_deref(), Some("bookworm-backports"));
+        assert_eq!(backports.priority, INSTALLED_PRIORITY);
+
+        let experimental = Release::parse("Suite: experimental\nNotAutomatic: yes\n");
+        assert_eq!(experimental.priority, 1);
+
+        let but_automatic_alone = Release::parse("Suite: testing\nButAutomaticUpgrades: yes\n");
+        assert_eq!(but_automatic_alone.priority, 500);
+    }
+
+    #[test]
+    fn list_compression_uses_the_packages_suffix() {
+        let list = |name: &str| list_compression(Path::new(name)).map(str::to_string);
+        let base = "deb.debian.org_debian_dists_bookworm_main_binary-amd64_Packages";
+        assert_eq!(list(base).as_deref(), Some(""));
+        assert_eq!(list(&format!("{}.lz4", base)).as_deref(), Some(".lz4"));
+        assert_eq!(list(&format!("{}.diff_Index", base)), None);
+        assert_eq!(list("deb.debian.org_debian_dists_bookworm_InRelease"), None);
+    }
+
+    #[test]
+    fn outdated_picks_candidates_by_priority() {
+        // linux-image-amd64 is installed at stable's version, so stable's
+        // priority keeps it there despite the newer backport
+        let outdated = AptSource::with_root(fixture("apt")).outdated().unwrap();
+        let found: Vec<(&str, &str, Option<&str>, Option<&str>)> = outdated
+            .iter()
+            .map(|p| {
+                (
+                    p.name.as_str(),
+                    p.version.as_str(),
+                    p.available_version.as_deref(),
+                    p.repo.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            [
+                // Stable beats the newer backport of a lower priority
+                ("bash", "5.2.15-2+b2", Some("5.2.15-2+b7"), Some("stable")),
+                // Installed from backports, which upgrade it; stable's older
+                // version would be a downgrade
+                (
+                    "zfsutils-linux",
+                    "2.2.2-4~bpo12+1",
+                    Some("2.2.3-1~bpo12+1"),
+                    Some("stable-backports")
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,353 @@
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use flate2::read::GzDecoder;
+
+use super::apt::{compare_versions, field, stanzas};
+use crate::fetch::events::parse_datetime;
+use crate::fetch::{EventAction, FetchError, PackageEvent, PackageManager};
+
+/// dpkg's log of every package it unpacked or removed, relative to the root.
+pub const DPKG_LOG: &str = "var/log/dpkg.log";
+
+/// apt's log of its runs and the command lines behind them, relative to the
+/// root.
+pub const APT_HISTORY: &str = "var/log/apt/history.log";
+
+/// One apt run recorded in `history.log`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AptTransaction {
+    pub start: i64,
+    pub end: Option<i64>,
+    pub command: Option<String>,
+    pub changes: Vec<PackageEvent>, // stamped with the start time
+}
+
+impl AptTransaction {
+    fn contains(&self, timestamp: i64) -> bool {
+        self.start <= timestamp && timestamp <= self.end.unwrap_or(self.start)
+    }
+}
+
+/// Reads the events from `dpkg.log` and apt's `history.log` under `root`,
+/// including their logrotate copies.
+///
+/// Events come from dpkg, which also sees `dpkg -i`, and are tagged with
+/// the apt transaction that was running at the time. Transactions whose
+/// dpkg log has already been rotated away still contribute the changes
+/// apt recorded.
+pub fn read_events(root: &Path) -> Result<Vec<PackageEvent>, FetchError> {
+    let dpkg = parse_dpkg_log(&read_rotated(&root.join(DPKG_LOG))?);
+    let transactions = parse_history(&read_rotated(&root.join(APT_HISTORY))?);
+    Ok(merge(dpkg, transactions))
+}
+
+/// Reads a log and its rotated copies (`dpkg.log.1`, `dpkg.log.2.gz`, ...),
+/// oldest first. A log that does not exist reads as empty.
+fn read_rotated(log: &Path) -> Result<String, FetchError> {
+    let (Some(dir), Some(base)) = (log.parent(), log.file_name().and_then(OsStr::to_str)) else {
+        return Ok(String::new());
+    };
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(String::new()),
+        Err(e) => return Err(FetchError::io(dir, e)),
+    };
+
+    // The live log is generation 0, `.1` is 1 and so on
+    let mut files: Vec<(u32, PathBuf)> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let name = name.to_str()?;
+            let generation = match name.strip_prefix(base)? {
+                "" => 0,
+                suffix => {
+                    let suffix = suffix.strip_prefix('.')?;
+                    suffix.strip_suffix(".gz").unwrap_or(suffix).parse().ok()?
+                }
+            };
+            Some((generation, entry.path()))
+        })
+        .collect();
+    files.sort_by_key(|(generation, _)| std::cmp::Reverse(*generation));
+
+    let mut contents = String::new();
+    for (_, path) in files {
+        let bytes = fs::read(&path).map_err(|e| FetchError::io(&path, e))?;
+        if path.extension() == Some(OsStr::new("gz")) {
+            let mut text = Vec::new();
+            GzDecoder::new(bytes.as_slice())
+                .read_to_end(&mut text)
+                .map_err(|e| FetchError::io(&path, e))?;
+            contents.push_str(&String::from_utf8_lossy(&text));
+        } else {
+            contents.push_str(&String::from_utf8_lossy(&bytes));
+        }
+        if !contents.ends_with('\n') {
+            contents.push('\n');
+        }
+    }
+    Ok(contents)
+}
+
+/// Strips the architecture from a `name:arch` package.
+fn package_name(package: &str) -> &str {
+    package.split_once(':').map_or(package, |(name, _)| name)
+}
+
+/// Parses the action lines of a `dpkg.log`, e.g.
+/// `2024-01-15 10:30:45 upgrade foo:amd64 1.0-1 1.1-1`.
+///
+/// dpkg logs an upgrade to the same or an older version as an upgrade, so
+/// the versions decide between upgrades, downgrades and reinstalls. A purge
+/// right after a removal of the same package is the same event.
+pub fn parse_dpkg_log(contents: &str) -> Vec<PackageEvent> {
+    let mut events: Vec<PackageEvent> = Vec::new();
+    for line in contents.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let [date, time, action, package, old, new] = parts[..] else {
+            continue;
+        };
+        let version = |v: &str| Some(v.to_string()).filter(|v| v != "<none>");
+        let (old_version, new_version) = (version(old), version(new));
+        let name = package_name(package);
+
+        let action = match action {
+            "install" => EventAction::Installed,
+            "upgrade" => match (&old_version, &new_version) {
+                (Some(old), Some(new)) => match compare_versions(old, new) {
+                    std::cmp::Ordering::Less => EventAction::Upgraded,
+                    std::cmp::Ordering::Equal => EventAction::Reinstalled,
+                    std::cmp::Ordering::Greater => EventAction::Downgraded,
+                },
+                _ => EventAction::Upgraded,
+            },
+            "remove" => EventAction::Removed,
+            "purge" => {
+                let removed = events
+                    .iter()
+                    .rev()
+                    .find(|e| e.name == name)
+                    .is_some_and(|e| e.action == EventAction::Removed);
+                if removed {
+                    continue;
+                }
+                EventAction::Removed
+            }
+            _ => continue,
+        };
+        let Some(timestamp) = parse_datetime(&format!("{} {}", date, time)) else {
+            continue;
+        };
+
+        events.push(PackageEvent {
+            timestamp,
+            source: PackageManager::Apt,
+            action,
+            name: name.to_string(),
+            // A fresh install over leftover config files reports their version
+            old_version: old_version.filter(|_| action != EventAction::Installed),
+            new_version: new_version.filter(|_| action != EventAction::Removed),
+            transaction: None,
+            command: None,
+        });
+    }
+    events
+}
+
+/// Parses apt's `history.log` into its transactions.
+pub fn parse_history(contents: &str) -> Vec<AptTransaction> {
+    stanzas(contents)
+        .filter_map(|fields| {
+            let start = parse_datetime(field(&fields, "Start-Date")?)?;
+            let command = field(&fields, "Commandline").map(str::to_string);
+
+            let mut changes = Vec::new();
+            for (key, action) in [
+                ("Install", EventAction::Installed),
+                ("Reinstall", EventAction::Reinstalled),
+                ("Upgrade", EventAction::Upgraded),
+                ("Downgrade", EventAction::Downgraded),
+                ("Remove", EventAction::Removed),
+                ("Purge", EventAction::Removed),
+            ] {
+                let Some(list) = field(&fields, key) else {
+                    continue;
+                };
+                changes.extend(parse_changes(list).into_iter().map(|(name, versions)| {
+                    let (old_version, new_version) = match (action, versions.as_slice()) {
+                        (EventAction::Upgraded | EventAction::Downgraded, [old, new, ..]) => {
+                            (Some(old.clone()), Some(new.clone()))
+                        }
+                        (EventAction::Removed, [old, ..]) => (Some(old.clone()), None),
+                        (_, [new, ..]) => (None, Some(new.clone())),
+                        (_, []) => (None, None),
+                    };
+                    PackageEvent {
+                        timestamp: start,
+                        source: PackageManager::Apt,
+                        action,
+                        name,
+                        old_version,
+                        new_version,
+                        transaction: Some(start as u64),
+                        command: command.clone(),
+                    }
+                }));
+            }
+
+            Some(AptTransaction {
+                start,
+                end: field(&fields, "End-Date").and_then(parse_datetime),
+                command,
+                changes,
+            })
+        })
+        .collect()
+}
+
+/// Splits a `history.log` package list such as
+/// `foo:amd64 (1.0-1, automatic), bar:amd64 (1.0-1, 1.1-1)` into names and
+/// versions.
+fn parse_changes(list: &str) -> Vec<(String, Vec<String>)> {
+    list.split("), ")
+        .filter_map(|item| {
+            let (package, versions) = item.split_once(" (")?;
+            let versions = versions
+                .trim_end_matches(')')
+                .split(", ")
+                .filter(|v| *v != "automatic")
+                .map(str::to_string)
+                .collect();
+            Some((package_name(package.trim()).to_string(), versions))
+        })
+        .collect()
+}
+
+/// Tags the dpkg events with the transaction that was running when they
+/// happened, and adds the changes of transactions dpkg has no record of.
+fn merge(mut events: Vec<PackageEvent>, mut transactions: Vec<AptTransaction>) -> Vec<PackageEvent> {
+    transactions.sort_by_key(|t| t.start);
+
+    let mut seen: HashSet<(u64, String)> = HashSet::new();
+    for event in &mut events {
+        let i = transactions.partition_point(|t| t.start <= event.timestamp);
+        let Some(transaction) = i.checked_sub(1).map(|i| &transactions[i]) else {
+            continue;
+        };
+        if transaction.contains(event.timestamp) {
+            event.transaction = Some(transaction.start as u64);
+            event.command = transaction.command.clone();
+            seen.insert((transaction.start as u64, event.name.clone()));
+        }
+    }
+
+    for transaction in transactions {
+        events.extend(
+            transaction
+                .changes
+                .into_iter()
+                .filter(|change| !seen.contains(&(transaction.start as u64, change.name.clone()))),
+        );
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetch::fixture;
+
+    fn time(value: &str) -> i64 {
+        parse_datetime(value).unwrap()
+    }
+
+    #[test]
+    fn rotated_logs_oldest_first() {
+        let contents = read_rotated(&fixture("apt-log").join(DPKG_LOG)).unwrap();
+        // dpkg.log.2.gz, then dpkg.log.1, then dpkg.log
+        let position = |day: &str| contents.find(day).unwrap();
+        assert!(contents.starts_with("2024-01-10 "));
+        assert!(position("2024-01-10") < position("2024-01-12"));
+        assert!(position("2024-01-13") < position("2024-01-14"));
+
+        let missing = read_rotated(&fixture("missing").join(DPKG_LOG)).unwrap();
+        assert_eq!(missing, "");
+    }
+
+    #[test]
+    fn dpkg_actions_from_versions() {
+        let contents = read_rotated(&fixture("apt-log").join(DPKG_LOG)).unwrap();
+        let events = parse_dpkg_log(&contents);
+        let found: Vec<(&str, EventAction)> = events.iter().map(|e| (e.name.as_str(), e.action)).collect();
+        assert_eq!(
+            found,
+            [
+                ("htop", EventAction::Installed),
+                ("bash", EventAction::Upgraded),
+                ("mytool", EventAction::Installed),
+                // dpkg -i of the installed version
+                ("mytool", EventAction::Reinstalled),
+                // The purge right after the removal is folded into it
+                ("nano", EventAction::Removed),
+                ("libfoo", EventAction::Downgraded),
+                // A purge of leftover configuration is a removal of its own
+                ("oldpkg", EventAction::Removed),
+            ]
+        );
+
+        assert_eq!(events[0].old_version, None);
+        assert_eq!(events[0].new_version.as_deref(), Some("3.2.2-2"));
+        assert_eq!(events[4].old_version.as_deref(), Some("7.2-1"));
+        assert_eq!(events[4].new_version, None);
+        assert_eq!(events[4].timestamp, time("2024-01-14 15:00:00"));
+    }
+
+    #[test]
+    fn events_grouped_by_apt_transaction() {
+        let events = read_events(&fixture("apt-log")).unwrap();
+        let event = |name: &str| events.iter().find(|e| e.name == name).unwrap();
+
+        let htop = event("htop");
+        assert_eq!(htop.transaction, Some(time("2024-01-10 09:00:00") as u64));
+        assert_eq!(htop.command.as_deref(), Some("apt install htop"));
+        assert_eq!(event("bash").command.as_deref(), Some("apt upgrade"));
+        assert_eq!(event("libfoo").command.as_deref(), Some("apt install libfoo=1.0-1"));
+
+        // dpkg -i runs outside any apt transaction
+        assert_eq!(event("mytool").transaction, None);
+        assert_eq!(event("mytool").command, None);
+
+        // The purge is both in dpkg.log and history.log, but listed once
+        assert_eq!(events.iter().filter(|e| e.name == "nano").count(), 1);
+        assert_eq!(event("nano").command.as_deref(), Some("apt purge nano"));
+
+        // The dpkg log of the curl install has been rotated away, so its
+        // changes come from history.log
+        for name in ["curl", "libcurl4"] {
+            let event = event(name);
+            assert_eq!(event.action, EventAction::Installed);
+            assert_eq!(event.timestamp, time("2024-01-05 08:00:00"));
+            assert_eq!(event.new_version.as_deref(), Some("7.88.1-10"));
+            assert_eq!(event.command.as_deref(), Some("apt-get install curl"));
+        }
+        assert_eq!(events.len(), 9);
+    }
+
+    #[test]
+    fn history_change_lists() {
+        let changes = parse_changes("foo:amd64 (1.0-1, automatic), bar:i386 (1.0-1, 1.1-1), baz (2.0)");
+        assert_eq!(
+            changes,
+            [
+                ("foo".to_string(), vec!["1.0-1".to_string()]),
+                ("bar".to_string(), vec!["1.0-1".to_string(), "1.1-1".to_string()]),
+                ("baz".to_string(), vec!["2.0".to_string()]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,100 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+
+use super::pacman::{parse_desc, LOCAL_DB};
+use super::{command_exists, parse_package_lines, run_command, run_command_accepting, PackageSource};
+use crate::fetch::{FetchError, PackageInfo, PackageManager};
+
+/// Foreign packages installed from the AUR, queried through yay.
+///
+/// yay only reports names and versions; the rest comes from each package's
+/// entry in the pacman local database under `root`.
+pub struct AurSource {
+    root: PathBuf,
+}
+
+impl AurSource {
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+impl Default for AurSource {
+    fn default() -> Self {
+        Self::with_root("/")
+    }
+}
+
+impl PackageSource for AurSource {
+    fn manager(&self) -> PackageManager {
+        PackageManager::Aur
+    }
+
+    fn detect(&self) -> bool {
+        command_exists("yay")
+    }
+
+    fn installed(&self) -> Result<Vec<PackageInfo>, FetchError> {
+        let out = run_command("yay", &["-Qm"])?;
+        let db = self.root.join(LOCAL_DB);
+        Ok(parse_package_lines(&out, self.manager())
+            .into_iter()
+            .map(|package| {
+                let desc = db.join(format!("{}-{}", package.name, package.version)).join("desc");
+                match fs::read_to_string(desc).ok().as_deref().and_then(parse_desc) {
+                    Some(local) => PackageInfo {
+                        source: PackageManager::Aur,
+                        ..local
+                    },
+                    None => package,
+                }
+            })
+            .collect())
+    }
+
+    fn outdated(&self) -> Result<Vec<PackageInfo>, FetchError> {
+        // Only AUR updates; repo updates are pacman's. Like pacman -Q, exits
+        // with 1 when nothing matched
+        let out = run_command_accepting("yay", &["-Qua"], &[0, 1])?;
+        Ok(parse_package_lines(&out, self.manager()))
+    }
+}
+
+/// Drops the pacman entries of packages that the AUR source also lists.
+///
+/// pacman's local database holds every installed package, foreign ones
+/// included, so without this each AUR package would appear twice.
+pub fn remove_foreign_duplicates(packages: &mut Vec<PackageInfo>) {
+    let aur: HashSet<String> = packages
+        .iter()
+        .filter(|p| p.source == PackageManager::Aur)
+        .map(|p| p.name.clone())
+        .collect();
+    packages.retain(|p| p.source != PackageManager::Pacman || !aur.contains(&p.name));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn foreign_packages_are_listed_once() {
+        let mut packages = vec![
+            PackageInfo::new(PackageManager::Pacman, "bash"),
+            PackageInfo::new(PackageManager::Pacman, "yay-bin"),
+            PackageInfo::new(PackageManager::Aur, "yay-bin"),
+            PackageInfo::new(PackageManager::Flatpak, "bash"),
+        ];
+        remove_foreign_duplicates(&mut packages);
+        let found: Vec<(PackageManager, &str)> = packages.iter().map(|p| (p.source, p.name.as_str())).collect();
+        assert_eq!(
+            found,
+            [
+                (PackageManager::Pacman, "bash"),
+                (PackageManager::Aur, "yay-bin"),
+                (PackageManager::Flatpak, "bash"),
+            ]
+        );
+    }
+}
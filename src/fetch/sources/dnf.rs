@@ -0,0 +1,447 @@
+use std::collections::HashMap;
+
+use super::{command_exists, run_command, PackageSource};
+use crate::fetch::events::{parse_datetime, parse_month};
+use crate::fetch::{EventAction, FetchError, PackageEvent, PackageInfo, PackageManager};
+
+/// `rpm --queryformat` used to list installed packages, one per line with
+/// tab-separated fields.
+pub const QUERY_FORMAT: &str =
+    "%{NAME}\t%{EPOCH}\t%{VERSION}\t%{RELEASE}\t%{ARCH}\t%{INSTALLTIME}\t%{SIZE}\t%{SUMMARY}\n";
+
+/// `dnf repoquery --queryformat` used to list available upgrades. dnf5 needs
+/// the newline; dnf4 adds its own, leaving blank lines.
+pub const UPGRADES_FORMAT: &str = "%{name}\t%{arch}\t%{evr}\t%{repoid}\n";
+
+/// How many of the latest transactions are read from the history, all in
+/// one `history info` call. dnf only merges ID ranges (`1..3`); IDs listed
+/// one by one are printed as separate transactions.
+pub const HISTORY_TRANSACTIONS: usize = 20;
+
+/// Fedora packages managed by dnf/rpm.
+pub struct DnfSource;
+
+impl PackageSource for DnfSource {
+    fn manager(&self) -> PackageManager {
+        PackageManager::Dnf
+    }
+
+    fn detect(&self) -> bool {
+        command_exists("dnf")
+    }
+
+    fn installed(&self) -> Result<Vec<PackageInfo>, FetchError> {
+        let out = run_command("rpm", &["-qa", "--queryformat", QUERY_FORMAT])?;
+        parse_query_output(&out)
+    }
+
+    fn outdated(&self) -> Result<Vec<PackageInfo>, FetchError> {
+        let out = run_command(
+            "dnf",
+            &["repoquery", "--upgrades", "--latest-limit", "1", "--queryformat", UPGRADES_FORMAT],
+        )?;
+        let installed = self.installed()?;
+        Ok(parse_upgrades(&out, &installed))
+    }
+
+    /// Reads the latest [`HISTORY_TRANSACTIONS`] transactions from dnf's
+    /// history.
+    fn events(&self) -> Result<Vec<PackageEvent>, FetchError> {
+        let list = run_command("dnf", &["history", "list"])?;
+        let ids: Vec<String> = parse_history_ids(&list)
+            .into_iter()
+            .take(HISTORY_TRANSACTIONS)
+            .map(|id| id.to_string())
+            .collect();
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut args = vec!["history", "info"];
+        args.extend(ids.iter().map(String::as_str));
+        let info = run_command("dnf", &args)?;
+        let mut events = Vec::new();
+        for transaction in split_history_info(&info) {
+            events.extend(parse_history_info(transaction)?);
+        }
+        Ok(events)
+    }
+}
+
+/// Parses `rpm -qa` output produced with [`QUERY_FORMAT`].
+///
+/// Name, epoch, version, release and arch end up in separate fields, so the
+/// NEVRA string never has to be split apart.
+///
+/// rpm prints `(none)` for unset tags, which becomes `None` here. A row
+/// with missing columns means the query format was not applied, so it fails
+/// the whole listing rather than yielding half-filled packages.
+pub fn parse_query_output(output: &str) -> Result<Vec<PackageInfo>, FetchError> {
+    output
+        .lines()
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.splitn(8, '\t').collect();
+            if parts.len() < 8 {
+                return Some(Err(FetchError::Parse {
+                    input: "rpm -qa output".to_string(),
+                    message: format!("expected 8 columns in {:?}", line),
+                }));
+            }
+            // gpg-pubkey entries are imported signing keys, not packages
+            if parts[0].is_empty() || parts[0] == "gpg-pubkey" {
+                return None;
+            }
+            let tag = |i: usize| {
+                parts
+                    .get(i)
+                    .filter(|v| !v.is_empty() && **v != "(none)")
+                    .map(|v| v.to_string())
+            };
+
+            Some(Ok(PackageInfo {
+                epoch: tag(1).and_then(|e| e.parse().ok()),
+                version: parts[2].to_string(),
+                release: tag(3),
+                arch: tag(4),
+                install_date: tag(5).and_then(|t| t.parse().ok()),
+                size: tag(6).and_then(|s| s.parse().ok()),
+                description: tag(7),
+                ..PackageInfo::new(PackageManager::Dnf, parts[0])
+            }))
+        })
+        .collect()
+}
+
+/// Parses `dnf repoquery --upgrades` output produced with
+/// [`UPGRADES_FORMAT`].
+///
+/// The installed version is looked up in `installed` by name. Rows without
+/// the four columns, such as blank lines, are skipped.
+pub fn parse_upgrades(output: &str, installed: &[PackageInfo]) -> Vec<PackageInfo> {
+    let mut outdated = Vec::new();
+    for line in output.lines() {
+        let [name, arch, version, repo] = line.split('\t').collect::<Vec<_>>()[..] else {
+            continue;
+        };
+        let current = installed.iter().find(|p| p.name == name);
+
+        let mut package = PackageInfo::new(PackageManager::Dnf, name);
+        package.version = current.map(evr).unwrap_or_default();
+        package.available_version = Some(version.to_string());
+        package.arch = Some(arch.to_string());
+        package.repo = Some(repo.to_string());
+        outdated.push(package);
+    }
+    outdated
+}
+
+/// Parses the transaction IDs from `dnf history list`, newest first.
+///
+/// Rows start with the ID in both dnf4 (`5 | install foo | ...`) and dnf5
+/// (`5 dnf install foo ...`).
+pub fn parse_history_ids(output: &str) -> Vec<u64> {
+    let mut ids: Vec<u64> = output
+        .lines()
+        .filter_map(|line| line.split_whitespace().next()?.parse().ok())
+        .collect();
+    ids.sort_unstable_by(|a, b| b.cmp(a));
+    ids
+}
+
+/// Splits the output of `dnf history info` for several IDs into one part per
+/// transaction, each starting at its `Transaction ID` line.
+pub fn split_history_info(output: &str) -> Vec<&str> {
+    let mut starts = Vec::new();
+    let mut offset = 0;
+    for line in output.split_inclusive('\n') {
+        let key = line.split_once(':').map(|(key, _)| key.trim());
+        if key.is_some_and(|key| key.eq_ignore_ascii_case("transaction id")) {
+            starts.push(offset);
+        }
+        offset += line.len();
+    }
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, start)| &output[*start..starts.get(i + 1).copied().unwrap_or(output.len())])
+        .collect()
+}
+
+/// Parses the `dnf history info <id>` output of one transaction.
+///
+/// The begin (dnf5: start) time, command line and the `Packages Altered`
+/// rows are read from both the dnf4 and dnf5 layouts. Rows for the replaced side of an
+/// upgrade or downgrade (`Upgraded`, `Downgraded`, `Replaced`) supply the
+/// old version of the matching event.
+pub fn parse_history_info(output: &str) -> Result<Vec<PackageEvent>, FetchError> {
+    let parse_error = |message: &str| FetchError::Parse {
+        input: "dnf history info output".to_string(),
+        message: message.to_string(),
+    };
+
+    let mut id = None;
+    let mut begin = None;
+    let mut command = None;
+    let mut changes: Vec<(EventAction, &str, String)> = Vec::new();
+    let mut replaced: HashMap<&str, String> = HashMap::new();
+    let mut in_packages = false;
+
+    for line in output.lines() {
+        // Everything after the header is package rows, scriptlet output or
+        // blank lines; unknown first words are skipped below
+        if in_packages {
+            let mut words = line.split_whitespace().filter(|w| *w != "**");
+            let (Some(action), Some(nevra)) = (words.next(), words.next()) else {
+                continue;
+            };
+            let Some((name, version)) = split_nevra(nevra) else {
+                continue;
+            };
+            let action = match action {
+                "Install" | "Dep-Install" | "Obsoleting" => EventAction::Installed,
+                "Upgrade" => EventAction::Upgraded,
+                "Downgrade" => EventAction::Downgraded,
+                "Reinstall" => EventAction::Reinstalled,
+                "Erase" | "Remove" | "Removed" | "Obsoleted" => EventAction::Removed,
+                "Upgraded" | "Downgraded" | "Replaced" => {
+                    replaced.insert(name, version);
+                    continue;
+                }
+                // The old side of a reinstall, reason changes and the header
+                _ => continue,
+            };
+            changes.push((action, name, version));
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim().to_ascii_lowercase().as_str() {
+            "transaction id" => id = value.parse::<u64>().ok(),
+            // dnf5 calls the begin time the start time
+            "begin time" | "start time" => {
+                begin = parse_datetime(value).or_else(|| parse_ctime(value))
+            }
+            // dnf5 calls the command line the description
+            "command line" | "description" => {
+                command = Some(value.to_string()).filter(|c| !c.is_empty())
+            }
+            "packages altered" => in_packages = true,
+            _ => {}
+        }
+    }
+
+    let id = id.ok_or_else(|| parse_error("no transaction ID"))?;
+    let begin = begin.ok_or_else(|| parse_error("no begin time"))?;
+    Ok(changes
+        .into_iter()
+        .map(|(action, name, version)| {
+            let (old_version, new_version) = match action {
+                EventAction::Removed => (Some(version), None),
+                EventAction::Upgraded | EventAction::Downgraded => {
+                    (replaced.get(name).cloned(), Some(version))
+                }
+                _ => (None, Some(version)),
+            };
+            PackageEvent {
+                timestamp: begin,
+                source: PackageManager::Dnf,
+                action,
+                name: name.to_string(),
+                old_version,
+                new_version,
+                transaction: Some(id),
+                command: command.clone(),
+            }
+        })
+        .collect())
+}
+
+/// Splits `name-[epoch:]version-release.arch` into the name and the
+/// `[epoch:]version-release`, dropping a zero epoch.
+fn split_nevra(nevra: &str) -> Option<(&str, String)> {
+    let (nevr, _arch) = nevra.rsplit_once('.')?;
+    let mut parts = nevr.rsplitn(3, '-');
+    let release = parts.next()?;
+    let version = parts.next()?;
+    let name = parts.next()?;
+    let version = version.strip_prefix("0:").unwrap_or(version);
+    Some((name, format!("{}-{}", version, release)))
+}
+
+/// Parses a C locale `%c` time such as `Mon Jan 15 10:30:45 2024`, which
+/// dnf4 uses for the begin time.
+fn parse_ctime(value: &str) -> Option<i64> {
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    let [_weekday, month, day, time, year, ..] = parts[..] else {
+        return None;
+    };
+    let month = parse_month(month)?;
+    let day: u32 = day.parse().ok()?;
+    parse_datetime(&format!("{}-{:02}-{:02} {}", year, month, day, time))
+}
+
+/// Formats a package's `[epoch:]version-release` string.
+pub fn evr(package: &PackageInfo) -> String {
+    let mut evr = String::new();
+    if let Some(epoch) = package.epoch {
+        evr.push_str(&format!("{}:", epoch));
+    }
+    evr.push_str(&package.version);
+    if let Some(release) = &package.release {
+        evr.push_str(&format!("-{}", release));
+    }
+    evr
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetch::fixture;
+    use std::fs;
+
+    fn read(name: &str) -> String {
+        fs::read_to_string(fixture("dnf").join(name)).unwrap()
+    }
+
+    #[test]
+    fn query_output_fields() {
+        let packages = parse_query_output(&read("rpm-query")).unwrap();
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        // Imported signing keys are not packages
+        assert_eq!(names, ["bash", "vim-enhanced", "fedora-release-common"]);
+
+        let bash = &packages[0];
+        assert_eq!(bash.epoch, None);
+        assert_eq!(bash.version, "5.2.26");
+        assert_eq!(bash.release.as_deref(), Some("1.fc39"));
+        assert_eq!(bash.arch.as_deref(), Some("x86_64"));
+        assert_eq!(bash.install_date, Some(1705314645));
+        assert_eq!(bash.size, Some(8127409));
+        assert_eq!(bash.description.as_deref(), Some("The GNU Bourne Again shell"));
+        assert_eq!(evr(bash), "5.2.26-1.fc39");
+
+        let vim = &packages[1];
+        assert_eq!(vim.epoch, Some(2));
+        assert_eq!(evr(vim), "2:9.1.031-1.fc39");
+
+        // noarch, with an empty summary
+        assert_eq!(packages[2].arch.as_deref(), Some("noarch"));
+        assert_eq!(packages[2].description, None);
+    }
+
+    #[test]
+    fn query_output_needs_every_column() {
+        assert!(parse_query_output("bash\t(none)\t5.2.26\t1.fc39\tx86_64\t1705314645\t8127409\n").is_err());
+        assert_eq!(parse_query_output("").unwrap().len(), 0);
+    }
+
+    #[test]
+    fn upgrades_with_installed_versions() {
+        let mut vim = PackageInfo::new(PackageManager::Dnf, "vim-enhanced");
+        vim.epoch = Some(2);
+        vim.version = "9.0.2120".to_string();
+        vim.release = Some("1.fc39".to_string());
+
+        let upgrades = parse_upgrades(&read("repoquery-upgrades"), &[vim]);
+        let found: Vec<(&str, &str, Option<&str>, Option<&str>)> = upgrades
+            .iter()
+            .map(|p| {
+                (
+                    p.name.as_str(),
+                    p.version.as_str(),
+                    p.available_version.as_deref(),
+                    p.repo.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("bash", "", Some("5.2.26-1.fc39"), Some("updates")),
+                // Long names stay on their row
+                ("kernel-modules-extra-matched", "", Some("6.7.4-200.fc39"), Some("updates")),
+                ("vim-enhanced", "2:9.0.2120-1.fc39", Some("2:9.1.031-1.fc39"), Some("updates-testing")),
+            ]
+        );
+        assert_eq!(upgrades[0].arch.as_deref(), Some("x86_64"));
+    }
+
+    #[test]
+    fn history_ids_from_both_layouts() {
+        assert_eq!(parse_history_ids(&read("dnf4-history-list")), [12, 11, 10, 9]);
+        assert_eq!(parse_history_ids(&read("dnf5-history-list")), [7, 6]);
+    }
+
+    #[test]
+    fn split_history_info_per_transaction() {
+        for (name, ids) in [("dnf4-history-info", &[12, 11, 10][..]), ("dnf5-history-info", &[7, 6])] {
+            let info = read(name);
+            let parts = split_history_info(&info);
+            assert_eq!(parts.len(), ids.len(), "{}", name);
+            for (part, id) in parts.iter().zip(ids) {
+                assert!(part.starts_with(&format!("Transaction ID : {}\n", id)), "{}", part);
+            }
+            assert_eq!(parts.concat(), info);
+        }
+    }
+
+    #[test]
+    fn dnf4_history_info() {
+        let info = read("dnf4-history-info");
+        let parts = split_history_info(&info);
+
+        let upgrade = parse_history_info(parts[0]).unwrap();
+        assert_eq!(
+            upgrade,
+            [PackageEvent {
+                timestamp: parse_datetime("2024-01-15 10:30:45").unwrap(),
+                source: PackageManager::Dnf,
+                action: EventAction::Upgraded,
+                name: "bash".to_string(),
+                old_version: Some("5.2.21-1.fc39".to_string()),
+                new_version: Some("5.2.26-1.fc39".to_string()),
+                transaction: Some(12),
+                command: Some("upgrade bash".to_string()),
+            }]
+        );
+
+        let removal = parse_history_info(parts[1]).unwrap();
+        assert_eq!(removal[0].action, EventAction::Removed);
+        assert_eq!(removal[0].old_version.as_deref(), Some("7.2-5.fc39"));
+        assert_eq!(removal[0].new_version, None);
+
+        let install = parse_history_info(parts[2]).unwrap();
+        let names: Vec<(&str, EventAction)> = install.iter().map(|e| (e.name.as_str(), e.action)).collect();
+        assert_eq!(names, [("htop", EventAction::Installed), ("hwloc-libs", EventAction::Installed)]);
+    }
+
+    #[test]
+    fn dnf5_history_info() {
+        let info = read("dnf5-history-info");
+        let parts = split_history_info(&info);
+
+        let install = parse_history_info(parts[0]).unwrap();
+        assert_eq!(install.len(), 2);
+        assert_eq!(install[0].timestamp, parse_datetime("2024-03-12 10:41:22").unwrap());
+        assert_eq!(install[0].command.as_deref(), Some("dnf5 install htop"));
+        assert_eq!(install[1].name, "hwloc-libs");
+        assert_eq!(install[1].new_version.as_deref(), Some("2.10.0-3.fc40"));
+
+        let upgrade = parse_history_info(parts[1]).unwrap();
+        assert_eq!(upgrade.len(), 1);
+        assert_eq!(upgrade[0].action, EventAction::Upgraded);
+        assert_eq!(upgrade[0].old_version.as_deref(), Some("5.2.21-1.fc40"));
+        assert_eq!(upgrade[0].new_version.as_deref(), Some("5.2.26-3.fc40"));
+        assert_eq!(upgrade[0].transaction, Some(6));
+    }
+
+    #[test]
+    fn history_info_without_a_time_fails() {
+        let info = "Transaction ID : 3\nCommand Line   : install foo\nPackages Altered:\n    Install foo-1.0-1.fc39.x86_64 @fedora\n";
+        assert!(parse_history_info(info).is_err());
+    }
+}
@@ -0,0 +1,343 @@
+use std::collections::HashSet;
+
+use super::{command_exists, run_command, PackageSource};
+use crate::fetch::events::{parse_datetime, parse_month};
+use crate::fetch::{
+    EventAction, FetchError, InstallScope, PackageEvent, PackageInfo, PackageKind, PackageManager,
+};
+use crate::utils::{civil_from_days, format_timestamp, unix_now};
+
+/// Columns requested from `flatpak list`, in output order.
+const LIST_COLUMNS: &str =
+    "--columns=application,version,branch,arch,origin,installation,size,runtime,description";
+
+/// Columns requested from `flatpak remote-ls --updates`, in output order.
+const UPDATE_COLUMNS: &str = "--columns=application,version,branch,origin,commit";
+
+/// Columns requested from `flatpak history`, in output order.
+const HISTORY_COLUMNS: &str = "--columns=time,change,application,version";
+
+/// How many days of `flatpak history` are read. The history prints times
+/// without a year, which can only be placed within a one-year window.
+const HISTORY_DAYS: i64 = 364;
+
+/// Flatpak applications and runtimes, from every installation (system and
+/// user).
+pub struct FlatpakSource;
+
+impl PackageSource for FlatpakSource {
+    fn manager(&self) -> PackageManager {
+        PackageManager::Flatpak
+    }
+
+    fn detect(&self) -> bool {
+        command_exists("flatpak")
+    }
+
+    fn installed(&self) -> Result<Vec<PackageInfo>, FetchError> {
+        let apps = run_command("flatpak", &["list", "--app", LIST_COLUMNS])?;
+        let runtimes = run_command("flatpak", &["list", "--runtime", LIST_COLUMNS])?;
+
+        let mut packages = parse_list(&apps, PackageKind::Application);
+        packages.extend(parse_list(&runtimes, PackageKind::Runtime));
+        mark_unused_runtimes(&mut packages, &apps);
+        Ok(packages)
+    }
+
+    /// Asks the remotes for newer commits of installed refs; this needs
+    /// the network.
+    fn outdated(&self) -> Result<Vec<PackageInfo>, FetchError> {
+        let out = run_command("flatpak", &["remote-ls", "--updates", UPDATE_COLUMNS])?;
+        let installed = self.installed()?;
+        Ok(parse_updates(&out, &installed))
+    }
+
+    /// Reads the last [`HISTORY_DAYS`] of `flatpak history`, which comes
+    /// from the systemd journal.
+    fn events(&self) -> Result<Vec<PackageEvent>, FetchError> {
+        let now = unix_now();
+        let since = format_timestamp(now - HISTORY_DAYS * 86_400);
+        let since = format!("--since={}", &since[..10]);
+        let out = run_command("flatpak", &["history", HISTORY_COLUMNS, &since])?;
+        Ok(parse_history(&out, now))
+    }
+}
+
+/// Splits a tab-separated row into trimmed columns, empty ones as `None`.
+fn columns(line: &str) -> Vec<Option<&str>> {
+    line.split('\t')
+        .map(|v| Some(v.trim()).filter(|v| !v.is_empty()))
+        .collect()
+}
+
+/// Parses tab-separated `flatpak list` output produced with [`LIST_COLUMNS`].
+pub fn parse_list(output: &str, kind: PackageKind) -> Vec<PackageInfo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let parts = columns(line);
+            let column = |i: usize| parts.get(i).copied().flatten().map(str::to_string);
+
+            let mut package = PackageInfo::new(PackageManager::Flatpak, column(0)?);
+            package.kind = Some(kind);
+            package.version = column(1).unwrap_or_default();
+            package.branch = column(2);
+            package.arch = column(3);
+            package.repo = column(4);
+            package.scope = column(5).map(|installation| match installation.as_str() {
+                "user" => InstallScope::User,
+                // "system" and custom system-wide installations
+                _ => InstallScope::System,
+            });
+            package.size = column(6).and_then(|size| parse_size(&size));
+            package.description = column(8);
+            Some(package)
+        })
+        .collect()
+}
+
+/// Parses a size as printed by flatpak (`g_format_size`), e.g. `1.2 GB`
+/// or `650.1 kB`. Units are powers of 1000.
+pub fn parse_size(size: &str) -> Option<u64> {
+    let mut parts = size.split_whitespace();
+    let number: f64 = parts.next()?.parse().ok()?;
+    let multiplier = match parts.next().unwrap_or("bytes") {
+        "bytes" | "byte" | "B" => 1.0,
+        "kB" | "KB" => 1e3,
+        "MB" => 1e6,
+        "GB" => 1e9,
+        "TB" => 1e12,
+        _ => return None,
+    };
+    Some((number * multiplier).round() as u64)
+}
+
+/// Flags runtimes that no installed application runs on.
+///
+/// A runtime counts as used when an application names it (same ID and
+/// branch) in its `runtime` column, or when it is an extension of a used
+/// runtime or of an application, such as `org.freedesktop.Platform.GL.default`
+/// or `org.example.App.Locale`. `flatpak_list_apps` is the output of
+/// `flatpak list --app` with [`LIST_COLUMNS`].
+pub fn mark_unused_runtimes(packages: &mut [PackageInfo], flatpak_list_apps: &str) {
+    // Runtime refs look like "org.gnome.Platform/x86_64/45"
+    let used: HashSet<(String, Option<String>)> = flatpak_list_apps
+        .lines()
+        .filter_map(|line| {
+            let runtime = columns(line).get(7).copied().flatten()?;
+            let mut parts = runtime.split('/');
+            let id = parts.next()?.to_string();
+            let branch = parts.nth(1).map(str::to_string);
+            Some((id, branch))
+        })
+        .collect();
+
+    let parents: Vec<String> = used
+        .iter()
+        .map(|(id, _)| id.clone())
+        .chain(
+            packages
+                .iter()
+                .filter(|p| p.kind == Some(PackageKind::Application))
+                .map(|p| p.name.clone()),
+        )
+        .collect();
+
+    for package in packages.iter_mut() {
+        if package.kind != Some(PackageKind::Runtime) {
+            continue;
+        }
+        let named = used.contains(&(package.name.clone(), package.branch.clone()));
+        let extension = parents.iter().any(|parent| {
+            package
+                .name
+                .strip_prefix(parent.as_str())
+                .is_some_and(|rest| rest.starts_with('.'))
+        });
+        package.unused = !named && !extension;
+    }
+}
+
+/// Parses `flatpak history` output produced with [`HISTORY_COLUMNS`].
+///
+/// Times look like `Jan 15 10:30:45` and are placed in the latest year that
+/// does not put them after `now`. Remote changes are skipped.
+pub fn parse_history(output: &str, now: i64) -> Vec<PackageEvent> {
+    let (year, _, _) = civil_from_days(now.div_euclid(86_400));
+    output
+        .lines()
+        .filter_map(|line| {
+            let parts = columns(line);
+            let column = |i: usize| parts.get(i).copied().flatten();
+
+            let mut time = column(0)?.split_whitespace();
+            let month = parse_month(time.next()?)?;
+            let day: u32 = time.next()?.parse().ok()?;
+            let clock = time.next()?;
+            let at = |year: i64| parse_datetime(&format!("{}-{:02}-{:02} {}", year, month, day, clock));
+            let timestamp = at(year).filter(|t| *t <= now + 86_400).or_else(|| at(year - 1))?;
+
+            let change = column(1)?;
+            let action = if change.contains("uninstall") {
+                EventAction::Removed
+            } else if change.contains("install") {
+                EventAction::Installed
+            } else if change.contains("update") {
+                EventAction::Upgraded
+            } else {
+                return None;
+            };
+
+            Some(PackageEvent {
+                timestamp,
+                source: PackageManager::Flatpak,
+                action,
+                name: column(2)?.to_string(),
+                old_version: None,
+                new_version: column(3)
+                    .filter(|_| action != EventAction::Removed)
+                    .map(str::to_string),
+                transaction: None,
+                command: None,
+            })
+        })
+        .collect()
+}
+
+/// Parses `flatpak remote-ls --updates` output produced with
+/// [`UPDATE_COLUMNS`], taking the installed version from `installed`.
+///
+/// Refs without a version string are shown with the first 12 characters of
+/// their new commit.
+pub fn parse_updates(output: &str, installed: &[PackageInfo]) -> Vec<PackageInfo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let parts = columns(line);
+            let column = |i: usize| parts.get(i).copied().flatten().map(str::to_string);
+            let name = column(0)?;
+            let branch = column(2);
+
+            let current = installed
+                .iter()
+                .find(|p| p.name == name && (branch.is_none() || p.branch == branch));
+            let mut package = match current {
+                Some(current) => current.clone(),
+                None => PackageInfo::new(PackageManager::Flatpak, name),
+            };
+            package.available_version = column(1)
+                .or_else(|| column(4).map(|commit| commit.chars().take(12).collect()));
+            package.branch = branch.or(package.branch);
+            package.repo = column(3).or(package.repo);
+            Some(package)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetch::fixture;
+    use std::fs;
+
+    fn read(name: &str) -> String {
+        fs::read_to_string(fixture("flatpak").join(name)).unwrap()
+    }
+
+    fn installed() -> Vec<PackageInfo> {
+        let apps = read("list-app");
+        let mut packages = parse_list(&apps, PackageKind::Application);
+        packages.extend(parse_list(&read("list-runtime"), PackageKind::Runtime));
+        mark_unused_runtimes(&mut packages, &apps);
+        packages
+    }
+
+    #[test]
+    fn list_columns() {
+        let packages = installed();
+        assert_eq!(packages.len(), 10);
+
+        let firefox = &packages[0];
+        assert_eq!(firefox.name, "org.mozilla.firefox");
+        assert_eq!(firefox.kind, Some(PackageKind::Application));
+        assert_eq!(firefox.version, "124.0.1");
+        assert_eq!(firefox.branch.as_deref(), Some("stable"));
+        assert_eq!(firefox.arch.as_deref(), Some("x86_64"));
+        assert_eq!(firefox.repo.as_deref(), Some("flathub"));
+        assert_eq!(firefox.scope, Some(InstallScope::System));
+        assert_eq!(firefox.size, Some(273_500_000));
+        assert_eq!(firefox.description.as_deref(), Some("Fast, Private & Safe Web Browser"));
+
+        assert_eq!(packages[1].scope, Some(InstallScope::User));
+        // Runtimes without a version string
+        assert_eq!(packages[6].kind, Some(PackageKind::Runtime));
+        assert_eq!(packages[6].version, "");
+    }
+
+    #[test]
+    fn unused_runtimes() {
+        let packages = installed();
+        let unused: Vec<(&str, Option<&str>)> = packages
+            .iter()
+            .filter(|p| p.unused)
+            .map(|p| (p.name.as_str(), p.branch.as_deref()))
+            .collect();
+        // Used: the runtimes the apps name, with the same branch, and the
+        // extensions of those runtimes and of the apps (GL.default, openh264,
+        // firefox.Locale)
+        assert_eq!(
+            unused,
+            [
+                ("org.freedesktop.Platform", Some("22.08")),
+                ("org.gnome.Platform", Some("44")),
+                ("org.gtk.Gtk3theme.Adwaita-dark", Some("3.22")),
+            ]
+        );
+    }
+
+    #[test]
+    fn extension_needs_a_dot_after_the_parent() {
+        let apps = "org.example.App\t1.0\tstable\tx86_64\tflathub\tsystem\t1 MB\torg.example.Platform/x86_64/1\t\n";
+        let mut packages = parse_list(apps, PackageKind::Application);
+        packages.extend(parse_list(
+            "org.example.PlatformTheme\t\t1\tx86_64\tflathub\tsystem\t1 MB\t\t\n",
+            PackageKind::Runtime,
+        ));
+        mark_unused_runtimes(&mut packages, apps);
+        assert!(packages[1].unused);
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(parse_size("112 bytes"), Some(112));
+        assert_eq!(parse_size("1 byte"), Some(1));
+        assert_eq!(parse_size("18.3 kB"), Some(18_300));
+        assert_eq!(parse_size("1.1 GB"), Some(1_100_000_000));
+        assert_eq!(parse_size("2 TB"), Some(2_000_000_000_000));
+        assert_eq!(parse_size("1.5 MiB"), None);
+        assert_eq!(parse_size(""), None);
+    }
+
+    #[test]
+    fn history_dates_in_the_year_before_now() {
+        let now = parse_datetime("2024-03-01 12:00:00").unwrap();
+        let events = parse_history(&read("history"), now);
+        let found: Vec<(i64, EventAction, &str, Option<&str>)> = events
+            .iter()
+            .map(|e| (e.timestamp, e.action, e.name.as_str(), e.new_version.as_deref()))
+            .collect();
+        let at = |time: &str| parse_datetime(time).unwrap();
+        assert_eq!(
+            found,
+            [
+                // After now in 2024, so last year
+                (at("2023-03-05 10:00:00"), EventAction::Installed, "org.gnome.Calculator", Some("44.0")),
+                (at("2023-12-28 09:00:00"), EventAction::Installed, "org.mozilla.firefox", Some("121.0")),
+                // The remote change is skipped; Feb 29 only exists in 2024
+                (at("2024-02-29 08:00:00"), EventAction::Upgraded, "org.mozilla.firefox", Some("123.0")),
+                // Within a day of now is allowed for time zone differences
+                (at("2024-03-02 01:00:00"), EventAction::Removed, "org.gnome.Calculator", None),
+            ]
+        );
+    }
+}
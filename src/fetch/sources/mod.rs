@@ -0,0 +1,239 @@
+use std::io::{self, Read};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::fetch::error::{FetchError, Operation, SourceError};
+use crate::fetch::{PackageEvent, PackageInfo, PackageManager};
+
+pub mod apt;
+pub mod apt_log;
+pub mod aur;
+pub mod dnf;
+pub mod flatpak;
+pub mod pacman;
+
+pub use apt::AptSource;
+pub use aur::AurSource;
+pub use dnf::DnfSource;
+pub use flatpak::FlatpakSource;
+pub use pacman::PacmanSource;
+
+/// How long a helper program may run before it is killed.
+pub const COMMAND_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A package manager backend.
+///
+/// Each implementation knows how to detect its manager on the running system
+/// and how to list installed and outdated packages and past package events.
+/// Failures are returned rather than printed, so an empty list always means
+/// there was nothing to report.
+pub trait PackageSource: Send + Sync {
+    /// The manager stored in `PackageInfo.source`.
+    fn manager(&self) -> PackageManager;
+
+    /// Returns true if this manager is available on the system.
+    fn detect(&self) -> bool;
+
+    fn installed(&self) -> Result<Vec<PackageInfo>, FetchError>;
+
+    fn outdated(&self) -> Result<Vec<PackageInfo>, FetchError> {
+        Ok(Vec::new())
+    }
+
+    /// Install, upgrade and removal events from the manager's logs, in
+    /// any order.
+    fn events(&self) -> Result<Vec<PackageEvent>, FetchError> {
+        Ok(Vec::new())
+    }
+}
+
+/// The set of package sources the app queries.
+pub struct Registry {
+    sources: Vec<Arc<dyn PackageSource>>,
+}
+
+impl Registry {
+    /// Creates a registry with no sources registered.
+    pub fn empty() -> Self {
+        Self {
+            sources: Vec::new(),
+        }
+    }
+
+    pub fn register<S: PackageSource + 'static>(&mut self, source: S) {
+        self.sources.push(Arc::new(source));
+    }
+
+    /// Keeps only the sources for which `keep` returns true.
+    pub fn retain<F: FnMut(&dyn PackageSource) -> bool>(&mut self, mut keep: F) {
+        self.sources.retain(|s| keep(s.as_ref()));
+    }
+
+    pub fn sources(&self) -> impl Iterator<Item = &dyn PackageSource> {
+        self.sources.iter().map(|s| s.as_ref())
+    }
+
+    /// Shared handles to the sources, for querying them from worker threads.
+    pub fn shared(&self) -> impl Iterator<Item = Arc<dyn PackageSource>> + '_ {
+        self.sources.iter().cloned()
+    }
+
+    /// Returns the sources whose manager is present on this system.
+    pub fn detect(&self) -> Vec<&dyn PackageSource> {
+        self.sources().filter(|s| s.detect()).collect()
+    }
+
+    /// Installed packages from every detected source, each AUR package
+    /// listed once.
+    pub fn installed(&self) -> (Vec<PackageInfo>, Vec<SourceError>) {
+        let (mut packages, errors) = self.collect(Operation::Installed, |s| s.installed());
+        aur::remove_foreign_duplicates(&mut packages);
+        (packages, errors)
+    }
+
+    pub fn outdated(&self) -> (Vec<PackageInfo>, Vec<SourceError>) {
+        self.collect(Operation::Outdated, |s| s.outdated())
+    }
+
+    /// Events from every detected source, oldest first.
+    pub fn events(&self) -> (Vec<PackageEvent>, Vec<SourceError>) {
+        let (mut events, errors) = self.collect(Operation::History, |s| s.events());
+        events.sort_by_key(|e| e.timestamp);
+        (events, errors)
+    }
+
+    /// Runs `operation` on every detected source, keeping the results from
+    /// the ones that succeeded and the errors from the rest.
+    fn collect<T, F>(&self, operation: Operation, run: F) -> (Vec<T>, Vec<SourceError>)
+    where
+        F: Fn(&dyn PackageSource) -> Result<Vec<T>, FetchError>,
+    {
+        let mut packages = Vec::new();
+        let mut errors = Vec::new();
+        for source in self.detect() {
+            match run(source) {
+                Ok(found) => packages.extend(found),
+                Err(error) => errors.push(SourceError::new(source.manager(), operation, error)),
+            }
+        }
+        (packages, errors)
+    }
+}
+
+impl Default for Registry {
+    /// Creates a registry with all built-in sources.
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register(PacmanSource::default());
+        registry.register(AurSource::default());
+        registry.register(AptSource::default());
+        registry.register(DnfSource);
+        registry.register(FlatpakSource);
+        registry
+    }
+}
+
+/// Returns true if `program --version` can be executed.
+pub(crate) fn command_exists(program: &str) -> bool {
+    Command::new(program).arg("--version").output().is_ok()
+}
+
+/// Parses `name version` lines, as printed by `pacman -Q` style queries.
+///
+/// Update listings in the `name old -> new` form also fill in
+/// `available_version`.
+pub(crate) fn parse_package_lines(output: &str, source: PackageManager) -> Vec<PackageInfo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let mut package = PackageInfo::new(source, parts.next()?);
+            package.version = parts.next().unwrap_or_default().to_string();
+            if parts.next() == Some("->") {
+                package.available_version = parts.next().map(str::to_string);
+            }
+            Some(package)
+        })
+        .collect()
+}
+
+/// Runs a command and returns its stdout if it exited successfully.
+///
+/// The command runs in the C locale, so dates and headers come out the
+/// way the parsers expect them.
+pub(crate) fn run_command(program: &str, args: &[&str]) -> Result<String, FetchError> {
+    run_command_accepting(program, args, &[0])
+}
+
+/// Like [`run_command`], for programs that also use other exit codes to
+/// report success (`checkupdates` exits with 2 when there is nothing to do).
+///
+/// The program is killed after [`COMMAND_TIMEOUT`].
+pub(crate) fn run_command_accepting(
+    program: &str,
+    args: &[&str],
+    success_codes: &[i32],
+) -> Result<String, FetchError> {
+    let command = std::iter::once(program)
+        .chain(args.iter().copied())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut child = Command::new(program)
+        .args(args)
+        .env("LC_ALL", "C")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => FetchError::ToolMissing {
+                tool: program.to_string(),
+            },
+            _ => FetchError::io(program, e),
+        })?;
+
+    // Drain both pipes while waiting, so a chatty program cannot block
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+
+    let deadline = Instant::now() + COMMAND_TIMEOUT;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(FetchError::Timeout {
+                    command,
+                    seconds: COMMAND_TIMEOUT.as_secs(),
+                });
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(20)),
+            Err(e) => return Err(FetchError::io(program, e)),
+        }
+    };
+
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+    if !status.code().is_some_and(|code| success_codes.contains(&code)) {
+        return Err(FetchError::NonZeroExit {
+            command,
+            code: status.code(),
+            stderr: String::from_utf8_lossy(&stderr).trim().to_string(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&stdout).into_owned())
+}
+
+fn read_in_background<R: Read + Send + 'static>(pipe: Option<R>) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    })
+}
@@ -0,0 +1,226 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use super::{parse_package_lines, run_command_accepting, PackageSource};
+use crate::fetch::events::parse_datetime;
+use crate::fetch::{EventAction, FetchError, InstallReason, PackageEvent, PackageInfo, PackageManager};
+
+/// Location of the local package database relative to the root.
+pub(crate) const LOCAL_DB: &str = "var/lib/pacman/local";
+
+/// pacman's default log file relative to the root.
+const LOG_FILE: &str = "var/log/pacman.log";
+
+/// Official repository packages managed by pacman.
+///
+/// Installed packages come straight from the `desc` files of the local
+/// database under `root`, without running pacman.
+pub struct PacmanSource {
+    root: PathBuf,
+}
+
+impl PacmanSource {
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn local_db(&self) -> PathBuf {
+        self.root.join(LOCAL_DB)
+    }
+}
+
+impl Default for PacmanSource {
+    fn default() -> Self {
+        Self::with_root("/")
+    }
+}
+
+impl PackageSource for PacmanSource {
+    fn manager(&self) -> PackageManager {
+        PackageManager::Pacman
+    }
+
+    fn detect(&self) -> bool {
+        self.local_db().is_dir()
+    }
+
+    fn installed(&self) -> Result<Vec<PackageInfo>, FetchError> {
+        let db = self.local_db();
+        read_local_db(&db).map_err(|e| FetchError::io(db, e))
+    }
+
+    fn outdated(&self) -> Result<Vec<PackageInfo>, FetchError> {
+        // checkupdates comes from pacman-contrib, which is optional; without
+        // it this is reported as a warning for this source only. It exits
+        // with 2 when there are no updates.
+        let out = run_command_accepting("checkupdates", &[], &[0, 2])?;
+        Ok(parse_package_lines(&out, self.manager()))
+    }
+
+    fn events(&self) -> Result<Vec<PackageEvent>, FetchError> {
+        let log = self.root.join(LOG_FILE);
+        let contents = fs::read(&log).map_err(|e| FetchError::io(log, e))?;
+        Ok(parse_log(&String::from_utf8_lossy(&contents)))
+    }
+}
+
+/// Reads every `*/desc` entry of a pacman local database directory.
+///
+/// Entries that cannot be read or lack a name are skipped.
+pub fn read_local_db(db: &Path) -> io::Result<Vec<PackageInfo>> {
+    let mut packages = Vec::new();
+
+    for entry in fs::read_dir(db)? {
+        let path = entry?.path().join("desc");
+        if let Ok(contents) = fs::read_to_string(&path) {
+            if let Some(package) = parse_desc(&contents) {
+                packages.push(package);
+            }
+        }
+    }
+
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(packages)
+}
+
+/// Parses the package events of a `pacman.log`.
+///
+/// Lines look like `[2024-01-15T10:30:45+0100] [ALPM] upgraded foo (1.0-1 -> 1.1-1)`.
+/// Logs written before pacman 5.2 use `[2019-01-15 10:30]` local times without
+/// an offset.
+pub fn parse_log(contents: &str) -> Vec<PackageEvent> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let (time, rest) = line.strip_prefix('[')?.split_once(']')?;
+            let rest = rest.trim_start();
+            // Older logs have no [ALPM] tag
+            let rest = rest.strip_prefix("[ALPM]").unwrap_or(rest).trim_start();
+
+            let (action, rest) = rest.split_once(' ')?;
+            let action = match action {
+                "installed" => EventAction::Installed,
+                "upgraded" => EventAction::Upgraded,
+                "downgraded" => EventAction::Downgraded,
+                "reinstalled" => EventAction::Reinstalled,
+                "removed" => EventAction::Removed,
+                _ => return None,
+            };
+            let (name, versions) = rest.split_once(" (")?;
+            let versions = versions.strip_suffix(')')?;
+            let (old_version, new_version) = match versions.split_once(" -> ") {
+                Some((old, new)) => (Some(old), Some(new)),
+                None if action == EventAction::Removed => (Some(versions), None),
+                None => (None, Some(versions)),
+            };
+
+            Some(PackageEvent {
+                timestamp: parse_datetime(time)?,
+                source: PackageManager::Pacman,
+                action,
+                name: name.to_string(),
+                old_version: old_version.map(str::to_string),
+                new_version: new_version.map(str::to_string),
+                transaction: None,
+                command: None,
+            })
+        })
+        .collect()
+}
+
+/// Parses the contents of a pacman `desc` file.
+///
+/// The file is a list of `%FIELD%` headers, each followed by one value per
+/// line and terminated by a blank line.
+pub fn parse_desc(contents: &str) -> Option<PackageInfo> {
+    let mut package = PackageInfo::new(PackageManager::Pacman, "");
+    package.reason = Some(InstallReason::Explicit);
+
+    let mut lines = contents.lines();
+    while let Some(line) = lines.next() {
+        let Some(field) = line.strip_prefix('%').and_then(|l| l.strip_suffix('%')) else {
+            continue;
+        };
+        let values: Vec<&str> = lines.by_ref().take_while(|l| !l.is_empty()).collect();
+        let Some(first) = values.first().map(|v| v.to_string()) else {
+            continue;
+        };
+
+        match field {
+            "NAME" => package.name = first,
+            "VERSION" => package.version = first,
+            "DESC" => package.description = Some(first),
+            "ARCH" => package.arch = Some(first),
+            "INSTALLDATE" => package.install_date = first.parse().ok(),
+            "SIZE" => package.size = first.parse().ok(),
+            "PACKAGER" => package.packager = Some(first),
+            "LICENSE" => package.licenses = values.iter().map(|v| v.to_string()).collect(),
+            "REASON" if first == "1" => package.reason = Some(InstallReason::Dependency),
+            _ => {}
+        }
+    }
+
+    if package.name.is_empty() {
+        None
+    } else {
+        Some(package)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetch::fixture;
+
+    #[test]
+    fn parse_desc_reads_fields() {
+        let desc = "%NAME%\nfoo\n\n%VERSION%\n1:2.0-3\n\n%DESC%\nA test package\n\n\
+                    %ARCH%\nx86_64\n\n%INSTALLDATE%\n1700000000\n\n%SIZE%\n2048\n\n\
+                    %LICENSE%\nMIT\nApache-2.0\n\n%REASON%\n1\n\n";
+        let package = parse_desc(desc).unwrap();
+        assert_eq!(package.name, "foo");
+        assert_eq!(package.version, "1:2.0-3");
+        assert_eq!(package.description.as_deref(), Some("A test package"));
+        assert_eq!(package.arch.as_deref(), Some("x86_64"));
+        assert_eq!(package.install_date, Some(1_700_000_000));
+        assert_eq!(package.size, Some(2048));
+        assert_eq!(package.licenses, ["MIT", "Apache-2.0"]);
+        assert_eq!(package.reason, Some(InstallReason::Dependency));
+    }
+
+    #[test]
+    fn parse_desc_defaults_to_explicit() {
+        let package = parse_desc("%NAME%\nfoo\n\n%VERSION%\n1.0-1\n").unwrap();
+        assert_eq!(package.reason, Some(InstallReason::Explicit));
+        assert_eq!(package.size, None);
+    }
+
+    #[test]
+    fn parse_desc_requires_a_name() {
+        assert!(parse_desc("%VERSION%\n1.0-1\n\n").is_none());
+        assert!(parse_desc("").is_none());
+    }
+
+    #[test]
+    fn read_local_db_skips_unusable_entries() {
+        let packages = read_local_db(&fixture("pacman").join(LOCAL_DB)).unwrap();
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["bash", "zlib"]);
+
+        let zlib = &packages[1];
+        assert_eq!(zlib.version, "1:1.3.1-2");
+        assert_eq!(zlib.licenses, ["Zlib", "custom"]);
+        assert_eq!(zlib.reason, Some(InstallReason::Dependency));
+    }
+
+    #[test]
+    fn source_reads_from_root() {
+        let source = PacmanSource::with_root(fixture("pacman"));
+        assert!(source.detect());
+        assert_eq!(source.installed().unwrap().len(), 2);
+
+        let missing = PacmanSource::with_root(fixture("pacman").join("missing"));
+        assert!(!missing.detect());
+        assert!(missing.installed().is_err());
+    }
+}
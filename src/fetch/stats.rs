@@ -1,30 +1,64 @@
-use crate::fetch::PackageInfo;
-
-pub fn count_packages_by_source(packages: &[PackageInfo]) -> PackageCounts {
-    let mut counts = PackageCounts::default();
-    
-    for package in packages {
-        match package.source.as_str() {
-            "pacman" => counts.pacman += 1,
-            "aur" => counts.aur += 1,
-            "apt" => counts.apt += 1,
-            "dnf" => counts.dnf += 1,
-            "flatpak" => counts.flatpak += 1,
-            _ => counts.unknown += 1,
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::fetch::{InstallReason, PackageInfo, PackageKind, PackageManager};
+
+/// Package statistics, computed once per refresh from the collected lists.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PackageStats {
+    pub total: usize,
+    pub by_source: BTreeMap<PackageManager, usize>, // only sources with packages
+    pub outdated: usize,
+    pub outdated_by_source: BTreeMap<PackageManager, usize>,
+    pub explicit: usize,
+    pub dependency: usize,
+    pub installed_size: u64, // bytes, summed over packages that report a size
+    pub applications: usize, // Flatpak apps
+    pub runtimes: usize,     // Flatpak runtimes
+    pub unused_runtimes: usize,
+}
+
+impl PackageStats {
+    pub fn compute(packages: &[PackageInfo], outdated: &[PackageInfo]) -> Self {
+        let mut stats = PackageStats {
+            total: packages.len(),
+            outdated: outdated.len(),
+            ..Default::default()
+        };
+
+        for package in packages {
+            *stats.by_source.entry(package.source).or_insert(0) += 1;
+            match package.reason {
+                Some(InstallReason::Explicit) => stats.explicit += 1,
+                Some(InstallReason::Dependency) => stats.dependency += 1,
+                None => {}
+            }
+            stats.installed_size += package.size.unwrap_or(0);
+            match package.kind {
+                Some(PackageKind::Application) => stats.applications += 1,
+                Some(PackageKind::Runtime) => {
+                    stats.runtimes += 1;
+                    if package.unused {
+                        stats.unused_runtimes += 1;
+                    }
+                }
+                None => {}
+            }
         }
+
+        for package in outdated {
+            *stats.outdated_by_source.entry(package.source).or_insert(0) += 1;
+        }
+
+        stats
     }
-    
-    counts.total = counts.pacman + counts.aur + counts.apt + counts.dnf + counts.flatpak + counts.unknown;
-    counts
-}
 
-#[derive(Debug, Default)]
-pub struct PackageCounts {
-    pub total: usize,
-    pub pacman: usize,
-    pub aur: usize,
-    pub apt: usize,
-    pub dnf: usize,
-    pub flatpak: usize,
-    pub unknown: usize,
+    pub fn count(&self, manager: PackageManager) -> usize {
+        self.by_source.get(&manager).copied().unwrap_or(0)
+    }
+
+    pub fn outdated_count(&self, manager: PackageManager) -> usize {
+        self.outdated_by_source.get(&manager).copied().unwrap_or(0)
+    }
 }
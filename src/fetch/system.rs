@@ -0,0 +1,554 @@
+use std::env;
+use std::ffi::CStr;
+use std::fs;
+use std::mem::MaybeUninit;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::fetch::format_size;
+
+/// Reads kernel, distribution and hardware details from procfs and
+/// `os-release`.
+///
+/// All paths are resolved under `root`, so fixture directories can stand in
+/// for a real system. Session details (shell, terminal, desktop) come from
+/// the environment.
+pub struct SystemReader {
+    root: PathBuf,
+}
+
+impl SystemReader {
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn read(&self, path: &str) -> Option<String> {
+        fs::read_to_string(self.root.join(path))
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    }
+
+    /// Collects everything shown in the system panel.
+    pub fn system_info(&self) -> SystemInfo {
+        SystemInfo {
+            distro: self.distro(),
+            kernel: self.kernel(),
+            hostname: self.read("proc/sys/kernel/hostname"),
+            uptime: self.uptime(),
+            cpu: self.cpu(),
+            memory: self.read("proc/meminfo").map(|m| Memory::from_meminfo(&m)),
+            load_average: self.load_average(),
+            shell: env::var("SHELL")
+                .ok()
+                .and_then(|shell| shell.rsplit('/').next().map(str::to_string))
+                .filter(|shell| !shell.is_empty()),
+            terminal: terminal(),
+            desktop: desktop(),
+            init: self.init(),
+        }
+    }
+
+    /// Seconds since boot.
+    pub fn uptime(&self) -> Option<u64> {
+        let uptime = self.read("proc/uptime")?;
+        let seconds: f64 = uptime.split_whitespace().next()?.parse().ok()?;
+        Some(seconds as u64)
+    }
+
+    pub fn cpu(&self) -> Option<Cpu> {
+        let cpuinfo = self.read("proc/cpuinfo")?;
+        let value = |line: &str| line.split_once(':').map(|(_, v)| v.trim().to_string());
+
+        let model = cpuinfo
+            .lines()
+            .find(|line| line.starts_with("model name") || line.starts_with("Model"))
+            .and_then(value);
+        let cores = cpuinfo
+            .lines()
+            .filter(|line| line.starts_with("processor"))
+            .count();
+
+        Some(Cpu {
+            model: model.unwrap_or_else(|| "Unknown".to_string()),
+            cores,
+        })
+    }
+
+    /// The 1, 5 and 15 minute load averages.
+    pub fn load_average(&self) -> Option<[f64; 3]> {
+        let loadavg = self.read("proc/loadavg")?;
+        let mut values = loadavg.split_whitespace().map(|v| v.parse().ok());
+        Some([values.next()??, values.next()??, values.next()??])
+    }
+
+    /// Name of PID 1, with OpenRC and runit told apart from SysV init.
+    pub fn init(&self) -> Option<String> {
+        let comm = self.read("proc/1/comm")?;
+        if comm != "init" {
+            return Some(comm);
+        }
+        let init = if self.root.join("run/openrc").exists() {
+            "openrc"
+        } else if self.root.join("run/runit").exists() {
+            "runit"
+        } else {
+            "sysvinit"
+        };
+        Some(init.to_string())
+    }
+
+    /// Kernel details, the same data `uname -srvm` prints.
+    pub fn kernel(&self) -> Kernel {
+        Kernel {
+            name: self
+                .read("proc/sys/kernel/ostype")
+                .unwrap_or_else(|| "Linux".to_string()),
+            release: self.read("proc/sys/kernel/osrelease"),
+            version: self.read("proc/sys/kernel/version"),
+            arch: machine().unwrap_or_else(|| env::consts::ARCH.to_string()),
+        }
+    }
+
+    /// Parses `/etc/os-release`, falling back to `/usr/lib/os-release`.
+    pub fn distro(&self) -> Distro {
+        ["etc/os-release", "usr/lib/os-release"]
+            .iter()
+            .find_map(|path| fs::read_to_string(self.root.join(path)).ok())
+            .map(|contents| Distro::from_os_release(&contents))
+            .unwrap_or_default()
+    }
+}
+
+impl Default for SystemReader {
+    fn default() -> Self {
+        Self::with_root("/")
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Kernel {
+    pub name: String,            // "Linux"
+    pub release: Option<String>, // "6.16.7-zen1-1-zen"
+    pub version: Option<String>, // build string, "#1 SMP PREEMPT_DYNAMIC ..."
+    pub arch: String,            // "x86_64"
+}
+
+/// The distribution as described by `os-release`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Distro {
+    pub name: Option<String>,
+    pub pretty_name: Option<String>,
+    pub id: Option<String>,
+    pub id_like: Vec<String>,
+    pub version_id: Option<String>,
+}
+
+impl Distro {
+    pub fn from_os_release(contents: &str) -> Self {
+        let mut distro = Distro::default();
+
+        for line in contents.lines() {
+            let Some((key, value)) = line.trim().split_once('=') else {
+                continue;
+            };
+            let value = unquote(value);
+            if value.is_empty() {
+                continue;
+            }
+            match key {
+                "NAME" => distro.name = Some(value),
+                "PRETTY_NAME" => distro.pretty_name = Some(value),
+                "ID" => distro.id = Some(value.to_lowercase()),
+                "ID_LIKE" => {
+                    distro.id_like = value.split_whitespace().map(str::to_lowercase).collect()
+                }
+                "VERSION_ID" => distro.version_id = Some(value),
+                _ => {}
+            }
+        }
+
+        distro
+    }
+
+    /// Best name for display: `PRETTY_NAME`, then `NAME`, then `ID`.
+    pub fn display_name(&self) -> &str {
+        self.pretty_name
+            .as_deref()
+            .or(self.name.as_deref())
+            .or(self.id.as_deref())
+            .unwrap_or("Unknown")
+    }
+}
+
+/// The hardware name of the running kernel from `uname(2)`, which differs
+/// from the architecture this binary was built for when, say, a 32-bit
+/// build runs on a 64-bit kernel.
+fn machine() -> Option<String> {
+    let mut uts = MaybeUninit::<libc::utsname>::uninit();
+
+    // SAFETY: `uts` points to enough memory for a `utsname` struct, which is
+    // initialised on success.
+    let uts = unsafe {
+        if libc::uname(uts.as_mut_ptr()) != 0 {
+            return None;
+        }
+        uts.assume_init()
+    };
+
+    // SAFETY: the kernel NUL-terminates every `utsname` field.
+    let machine = unsafe { CStr::from_ptr(uts.machine.as_ptr()) };
+    Some(machine.to_string_lossy().into_owned()).filter(|m| !m.is_empty())
+}
+
+/// Strips shell-style quotes and backslash escapes from an `os-release`
+/// value.
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    let inner = value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+        .unwrap_or(value);
+
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(next) = chars.next() {
+                out.push(next);
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Hardware and session details for the system panel.
+///
+/// Fields that could not be read are `None`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SystemInfo {
+    pub distro: Distro,
+    pub kernel: Kernel,
+    pub hostname: Option<String>,
+    pub uptime: Option<u64>, // seconds
+    pub cpu: Option<Cpu>,
+    pub memory: Option<Memory>,
+    pub load_average: Option<[f64; 3]>,
+    pub shell: Option<String>,
+    pub terminal: Option<String>,
+    pub desktop: Option<String>,
+    pub init: Option<String>,
+}
+
+impl SystemInfo {
+    /// Formats one field for display, or `None` if it is unavailable.
+    pub fn field(&self, field: SystemField) -> Option<String> {
+        match field {
+            SystemField::Os => Some(self.distro.display_name().to_string()),
+            SystemField::Kernel => self
+                .kernel
+                .release
+                .as_ref()
+                .map(|release| format!("{} {}", release, self.kernel.arch)),
+            SystemField::Hostname => self.hostname.clone(),
+            SystemField::Uptime => self.uptime.map(format_uptime),
+            SystemField::Cpu => self
+                .cpu
+                .as_ref()
+                .map(|cpu| format!("{} ({})", cpu.model, cpu.cores)),
+            SystemField::Memory => self
+                .memory
+                .as_ref()
+                .map(|m| format_usage(m.used(), m.total)),
+            SystemField::Swap => self
+                .memory
+                .as_ref()
+                .filter(|m| m.swap_total > 0)
+                .map(|m| format_usage(m.swap_used(), m.swap_total)),
+            SystemField::Load => self
+                .load_average
+                .map(|[one, five, fifteen]| format!("{:.2} {:.2} {:.2}", one, five, fifteen)),
+            SystemField::Shell => self.shell.clone(),
+            SystemField::Terminal => self.terminal.clone(),
+            SystemField::Desktop => self.desktop.clone(),
+            SystemField::Init => self.init.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cpu {
+    pub model: String,
+    pub cores: usize, // logical processors
+}
+
+/// Memory and swap usage in bytes, from `/proc/meminfo`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Memory {
+    pub total: u64,
+    pub available: u64,
+    pub swap_total: u64,
+    pub swap_free: u64,
+}
+
+impl Memory {
+    pub fn from_meminfo(contents: &str) -> Self {
+        let mut memory = Memory::default();
+        for line in contents.lines() {
+            let mut parts = line.split_whitespace();
+            let (Some(key), Some(value)) = (parts.next(), parts.next()) else {
+                continue;
+            };
+            // Values are in KiB
+            let bytes = value.parse::<u64>().unwrap_or(0) * 1024;
+            match key {
+                "MemTotal:" => memory.total = bytes,
+                "MemAvailable:" => memory.available = bytes,
+                "SwapTotal:" => memory.swap_total = bytes,
+                "SwapFree:" => memory.swap_free = bytes,
+                _ => {}
+            }
+        }
+        memory
+    }
+
+    pub fn used(&self) -> u64 {
+        self.total.saturating_sub(self.available)
+    }
+
+    pub fn swap_used(&self) -> u64 {
+        self.swap_total.saturating_sub(self.swap_free)
+    }
+}
+
+/// A row of the system panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SystemField {
+    Os,
+    Kernel,
+    Hostname,
+    Uptime,
+    Cpu,
+    Memory,
+    Swap,
+    Load,
+    Shell,
+    Terminal,
+    Desktop,
+    Init,
+}
+
+impl SystemField {
+    pub const ALL: [SystemField; 12] = [
+        SystemField::Os,
+        SystemField::Kernel,
+        SystemField::Hostname,
+        SystemField::Uptime,
+        SystemField::Cpu,
+        SystemField::Memory,
+        SystemField::Swap,
+        SystemField::Load,
+        SystemField::Shell,
+        SystemField::Terminal,
+        SystemField::Desktop,
+        SystemField::Init,
+    ];
+
+    /// Identifier used on the command line, e.g. `"uptime"`.
+    pub fn id(&self) -> &'static str {
+        match self {
+            SystemField::Os => "os",
+            SystemField::Kernel => "kernel",
+            SystemField::Hostname => "hostname",
+            SystemField::Uptime => "uptime",
+            SystemField::Cpu => "cpu",
+            SystemField::Memory => "memory",
+            SystemField::Swap => "swap",
+            SystemField::Load => "load",
+            SystemField::Shell => "shell",
+            SystemField::Terminal => "terminal",
+            SystemField::Desktop => "de",
+            SystemField::Init => "init",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SystemField::Os => "OS",
+            SystemField::Kernel => "Kernel",
+            SystemField::Hostname => "Host",
+            SystemField::Uptime => "Uptime",
+            SystemField::Cpu => "CPU",
+            SystemField::Memory => "Memory",
+            SystemField::Swap => "Swap",
+            SystemField::Load => "Load",
+            SystemField::Shell => "Shell",
+            SystemField::Terminal => "Terminal",
+            SystemField::Desktop => "DE/WM",
+            SystemField::Init => "Init",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|field| field.id().eq_ignore_ascii_case(id))
+    }
+}
+
+/// Formats seconds as `3d 4h 12m`, leaving out leading zero units.
+pub fn format_uptime(seconds: u64) -> String {
+    let days = seconds / 86_400;
+    let hours = seconds % 86_400 / 3600;
+    let minutes = seconds % 3600 / 60;
+    if days > 0 {
+        format!("{}d {}h {}m", days, hours, minutes)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+fn format_usage(used: u64, total: u64) -> String {
+    let percentage = if total > 0 {
+        used as f64 / total as f64 * 100.0
+    } else {
+        0.0
+    };
+    format!(
+        "{} / {} ({:.0}%)",
+        format_size(used),
+        format_size(total),
+        percentage
+    )
+}
+
+/// The terminal emulator, as far as the environment tells.
+fn terminal() -> Option<String> {
+    let set = |name: &str| env::var_os(name).is_some();
+    if let Ok(program) = env::var("TERM_PROGRAM") {
+        return Some(program);
+    }
+    let known = if set("KITTY_WINDOW_ID") {
+        "kitty"
+    } else if set("ALACRITTY_WINDOW_ID") {
+        "alacritty"
+    } else if set("WEZTERM_PANE") {
+        "wezterm"
+    } else if set("KONSOLE_VERSION") {
+        "konsole"
+    } else if set("GNOME_TERMINAL_SCREEN") {
+        "gnome-terminal"
+    } else if set("TMUX") {
+        "tmux"
+    } else {
+        return env::var("TERM").ok();
+    };
+    Some(known.to_string())
+}
+
+/// The desktop environment or window manager, with the display server.
+fn desktop() -> Option<String> {
+    let name = env::var("XDG_CURRENT_DESKTOP")
+        .or_else(|_| env::var("DESKTOP_SESSION"))
+        .ok()
+        .filter(|name| !name.is_empty())?;
+    let server = if env::var_os("WAYLAND_DISPLAY").is_some() {
+        " (Wayland)"
+    } else if env::var_os("DISPLAY").is_some() {
+        " (X11)"
+    } else {
+        ""
+    };
+    Some(format!("{}{}", name, server))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetch::fixture;
+
+    fn os_release(id: &str) -> Distro {
+        Distro::from_os_release(&fs::read_to_string(fixture("os-release").join(id)).unwrap())
+    }
+
+    #[test]
+    fn derivatives_keep_their_id_and_base() {
+        for (id, pretty_name, id_like) in [
+            ("manjaro", "Manjaro Linux", &["arch"][..]),
+            ("endeavouros", "EndeavourOS", &["arch"]),
+            ("linuxmint", "Linux Mint 21.3", &["ubuntu", "debian"]),
+            ("pop", "Pop!_OS 22.04 LTS", &["ubuntu", "debian"]),
+            ("nobara", "Nobara Linux 39 (KDE Plasma)", &["rhel", "centos", "fedora"]),
+        ] {
+            let distro = os_release(id);
+            assert_eq!(distro.id.as_deref(), Some(id));
+            assert_eq!(distro.display_name(), pretty_name);
+            assert_eq!(distro.id_like, id_like, "{}", id);
+        }
+    }
+
+    #[test]
+    fn os_release_values_are_unquoted() {
+        // Single quotes
+        assert_eq!(os_release("endeavouros").name.as_deref(), Some("EndeavourOS"));
+        // Unquoted and double-quoted numbers
+        assert_eq!(os_release("nobara").version_id.as_deref(), Some("39"));
+        assert_eq!(os_release("linuxmint").version_id.as_deref(), Some("21.3"));
+        // Rolling releases have no version
+        assert_eq!(os_release("manjaro").version_id, None);
+
+        let distro = Distro::from_os_release("NAME=\"Say \\\"hi\\\"\"\nID=Custom\nPRETTY_NAME=\"\"\n");
+        assert_eq!(distro.name.as_deref(), Some("Say \"hi\""));
+        assert_eq!(distro.id.as_deref(), Some("custom"));
+        assert_eq!(distro.pretty_name, None);
+        assert_eq!(distro.display_name(), "Say \"hi\"");
+    }
+
+    #[test]
+    fn reader_uses_the_root() {
+        let reader = SystemReader::with_root(fixture("system"));
+
+        // No /etc/os-release, so /usr/lib/os-release is used
+        let distro = reader.distro();
+        assert_eq!(distro.id.as_deref(), Some("arch"));
+        assert_eq!(distro.display_name(), "Arch Linux");
+
+        let kernel = reader.kernel();
+        assert_eq!(kernel.name, "Linux");
+        assert_eq!(kernel.release.as_deref(), Some("6.7.4-arch1-1"));
+        assert!(kernel.version.unwrap().starts_with("#1 SMP"));
+
+        assert_eq!(reader.uptime(), Some(93784));
+        assert_eq!(reader.load_average(), Some([0.52, 0.58, 0.59]));
+        let cpu = reader.cpu().unwrap();
+        assert_eq!(cpu.model, "AMD Ryzen 7 5800U with Radeon Graphics");
+        assert_eq!(cpu.cores, 2);
+        // PID 1 is plain init, with OpenRC's run directory present
+        assert_eq!(reader.init().as_deref(), Some("openrc"));
+
+        let info = reader.system_info();
+        assert_eq!(info.hostname.as_deref(), Some("archbox"));
+        let memory = info.memory.unwrap();
+        assert_eq!(memory.total, 16318508 * 1024);
+        assert_eq!(memory.used(), (16318508 - 12238881) * 1024);
+        assert_eq!(memory.swap_used(), (8388604 - 8126460) * 1024);
+    }
+
+    #[test]
+    fn reader_without_files() {
+        let reader = SystemReader::with_root(fixture("missing"));
+        assert_eq!(reader.distro().display_name(), "Unknown");
+        assert_eq!(reader.kernel().name, "Linux");
+        assert_eq!(reader.kernel().release, None);
+        assert_eq!(reader.uptime(), None);
+        assert!(reader.cpu().is_none());
+        assert_eq!(reader.load_average(), None);
+        assert_eq!(reader.init(), None);
+    }
+}
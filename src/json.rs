@@ -0,0 +1,90 @@
+//! Machine-readable output.
+//!
+//! Every `--json` document is an object with a `schema_version` field plus
+//! some of the following keys:
+//!
+//! | key                 | type                | contents                          |
+//! |---------------------|---------------------|-----------------------------------|
+//! | `packages`          | array of packages   | installed packages                |
+//! | `outdated_packages` | array of packages   | packages with a pending update    |
+//! | `recent_events`     | array of events     | latest package events, newest first |
+//! | `events`            | array of events     | package event timeline, oldest first |
+//! | `disk_info`         | array of disks      | mounted disks and their usage     |
+//! | `system`            | system object       | distro, kernel, hardware, session |
+//! | `stats`             | stats object        | counts derived from the lists     |
+//! | `errors`            | array of errors     | sources that failed to report     |
+//!
+//! The full report (`package-fetch --json`) always has every key except
+//! `events`, whose latest entries it carries as `recent_events`; the
+//! subcommands only emit the keys they collect. See the README for the
+//! fields of each object.
+//!
+//! Fields are only ever added within a schema version. Renaming, removing
+//! or changing the meaning of a field bumps [`SCHEMA_VERSION`].
+
+use serde::Serialize;
+
+use crate::app::App;
+use crate::fetch::stats::PackageStats;
+use crate::fetch::system::SystemInfo;
+use crate::fetch::{DiskInfo, PackageEvent, PackageInfo, SourceError};
+
+/// Version of the JSON document layout.
+pub const SCHEMA_VERSION: u32 = 3;
+
+/// A JSON document. Keys left as `None` are omitted.
+#[derive(Debug, Serialize)]
+pub struct Report<'a> {
+    pub schema_version: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub packages: Option<&'a [PackageInfo]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outdated_packages: Option<&'a [PackageInfo]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recent_events: Option<Vec<&'a PackageEvent>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub events: Option<Vec<&'a PackageEvent>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disk_info: Option<&'a [DiskInfo]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<&'a SystemInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stats: Option<&'a PackageStats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errors: Option<&'a [SourceError]>,
+}
+
+impl<'a> Report<'a> {
+    /// An empty document with only the schema version set.
+    pub fn empty() -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            packages: None,
+            outdated_packages: None,
+            recent_events: None,
+            events: None,
+            disk_info: None,
+            system: None,
+            stats: None,
+            errors: None,
+        }
+    }
+
+    /// The full app state. The whole event timeline is left to `history`.
+    pub fn from_app(app: &'a App) -> Self {
+        Self {
+            packages: Some(&app.packages),
+            outdated_packages: Some(&app.outdated_packages),
+            recent_events: Some(app.recent_events().collect()),
+            disk_info: Some(&app.disk_info),
+            system: Some(&app.system),
+            stats: Some(&app.stats),
+            errors: Some(&app.errors),
+            ..Self::empty()
+        }
+    }
+
+    pub fn to_string_pretty(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
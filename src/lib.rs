@@ -1,4 +1,6 @@
 pub mod app;
+pub mod cli;
+pub mod json;
 pub mod fetch;
 pub mod ui;
 pub mod utils;
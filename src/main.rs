@@ -1,7 +1,32 @@
-use package_fetch::{App, ui::tui_app};
+use std::{env, process};
+
+use package_fetch::cli::{self, Cli, Command};
+use package_fetch::ui::tui_app;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let app = App::new();
+    let cli = match Cli::parse(env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, cli::USAGE);
+            process::exit(2);
+        }
+    };
+
+    if cli.command != Command::Tui {
+        if let Err(e) = cli::run(&cli) {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let app = match cli.app() {
+        Ok(app) => app,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+    };
     let mut terminal = tui_app::setup_terminal()?;
     
     let res = tui_app::run_tui(&mut terminal, app);
@@ -10,4 +35,4 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     res?;
     
     Ok(())
-}
\ No newline at end of file
+}
@@ -1,4 +1,11 @@
+use std::io::{self, IsTerminal};
+
 use crate::app::App;
+use crate::fetch::error::Operation;
+use crate::fetch::{format_size, PackageManager};
+use crate::utils::{
+    format_event_versions, format_relative_time, format_version_change, unix_now,
+};
 use tui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout},
@@ -8,13 +15,66 @@ use tui::{
     Frame,
 };
 
+/// Widest box the one-shot output will draw, even on very wide terminals.
+const MAX_BOX_WIDTH: usize = 72;
+
+/// Narrowest box drawn; on tinier terminals the box overflows instead.
+const MIN_BOX_WIDTH: usize = 32;
+
+/// Prints the neofetch-style one-shot output, sized to the terminal.
+///
+/// The logo is coloured only when stdout is a terminal.
 pub fn render_ascii_ui(app: &App) {
-    let stats = app.get_package_stats();
-    
-    // Output block dimensions
-    let total_box_width = 59;
+    let colored = io::stdout().is_terminal();
+    for line in ascii_ui_lines(app, terminal_width(), colored) {
+        println!("{}", line);
+    }
+}
+
+/// Builds the one-shot output for a terminal `width` columns wide: the
+/// distro logo on the left and the statistics box on the right.
+///
+/// The logo is dropped when there is no room for both.
+pub fn ascii_ui_lines(app: &App, width: usize, colored: bool) -> Vec<String> {
+    let logo_width = app.logo.as_ref().map_or(0, |logo| logo.width() + 2);
+    let logo = app
+        .logo
+        .as_ref()
+        .filter(|_| width >= logo_width + MIN_BOX_WIDTH);
+
+    let available = if logo.is_some() { width - logo_width } else { width };
+    let box_lines = stats_box(app, available.clamp(MIN_BOX_WIDTH, MAX_BOX_WIDTH));
+
+    let Some(logo) = logo else {
+        return box_lines;
+    };
+
+    // Leave a blank line above the logo so it lines up with the box title
+    let height = box_lines.len().max(logo.height() + 1);
+    (0..height)
+        .map(|i| {
+            let (logo_line, padding) = match i.checked_sub(1).filter(|i| *i < logo.height()) {
+                Some(i) => {
+                    let plain = logo.plain_line(i);
+                    let padding = logo_width - plain.chars().count();
+                    let line = if colored { logo.ansi_line(i) } else { plain };
+                    (line, padding)
+                }
+                None => (String::new(), logo_width),
+            };
+            let box_line = box_lines.get(i).map(String::as_str).unwrap_or("");
+            format!("{}{}{}", logo_line, " ".repeat(padding), box_line)
+                .trim_end()
+                .to_string()
+        })
+        .collect()
+}
+
+fn stats_box(app: &App, total_box_width: usize) -> Vec<String> {
+    let stats = &app.stats;
     let inner_content_width = total_box_width - 4;
-    
+    let mut lines = Vec::new();
+
     // Helper function for text centering
     let center_text = |text: &str, width: usize| {
         let text_len = text.chars().count();
@@ -26,61 +86,156 @@ pub fn render_ascii_ui(app: &App) {
         let right_padding = padding - left_padding;
         format!("{}{}{}", " ".repeat(left_padding), text, " ".repeat(right_padding))
     };
-    
-    // Helper function for formatting content lines
+
+    // Helper function for formatting content lines, clipping long ones
     let format_line_content = |content: &str| {
+        let content = truncate(content, inner_content_width);
         format!("| {:<width$} |", content, width = inner_content_width)
     };
-    
-    // Header output
-    println!("+{}", "-".repeat(total_box_width - 2));
-    println!("|{}|", center_text("PackageFetch", inner_content_width + 2));
-    println!("|{}|", " ".repeat(inner_content_width + 2));
-    
-    // "Recently Installed" section
-    println!("{}", format_line_content("[Recently Installed]"));
-    for package in &app.recent_packages {
-        println!("{}", format_line_content(&package.name));
+    let border = format!("+{}+", "-".repeat(total_box_width - 2));
+    let blank = format!("|{}|", " ".repeat(inner_content_width + 2));
+
+    // Header
+    lines.push(border.clone());
+    lines.push(format!("|{}|", center_text("PackageFetch", inner_content_width + 2)));
+    lines.push(blank.clone());
+
+    // "System" section
+    let system_rows = system_rows(app);
+    if !system_rows.is_empty() {
+        lines.push(format_line_content("[System]"));
+        for (label, value) in system_rows {
+            lines.push(format_line_content(&format!("{}: {}", label, value)));
+        }
+        lines.push(blank.clone());
     }
-    if app.recent_packages.is_empty() {
-        println!("{}", format_line_content("No recent packages"));
+
+    // "Recent Activity" section
+    lines.push(format_line_content("[Recent Activity]"));
+    let now = unix_now();
+    for event in app.recent_events() {
+        lines.push(format_line_content(&format!(
+            "{} {} {} ({})",
+            event.action,
+            event.name,
+            format_event_versions(event),
+            format_relative_time(event.timestamp, now)
+        )));
     }
-    println!("|{}|", " ".repeat(inner_content_width + 2));
-    
-    // "Package Statistics" section
-    println!("{}", format_line_content("[Package Statistics]"));
-    println!("{}", format_line_content(&format!("Total packages: {}", stats.total)));
-    
-    if stats.pacman > 0 {
-        println!("{}", format_line_content(&format!("Pacman packages: {}", stats.pacman)));
+    if app.events.is_empty() {
+        lines.push(format_line_content("No recent activity"));
     }
-    if stats.aur > 0 {
-        println!("{}", format_line_content(&format!("AUR packages: {}", stats.aur)));
+    lines.push(blank.clone());
+
+    // "Package Statistics" section, listing every detected source
+    lines.push(format_line_content("[Package Statistics]"));
+    lines.push(format_line_content(&format!("Total packages: {}", stats.total)));
+
+    let mut managers: Vec<PackageManager> = app.profile.managers.clone();
+    managers.extend(stats.by_source.keys());
+    managers.sort();
+    managers.dedup();
+    for manager in managers {
+        lines.push(format_line_content(&format!(
+            "{} packages: {}",
+            manager.display_name(),
+            stats.count(manager)
+        )));
+        if manager == PackageManager::Flatpak && stats.applications + stats.runtimes > 0 {
+            lines.push(format_line_content(&format!(
+                "  {} apps, {} runtimes ({} unused)",
+                stats.applications, stats.runtimes, stats.unused_runtimes
+            )));
+        }
     }
-    if stats.apt > 0 {
-        println!("{}", format_line_content(&format!("APT packages: {}", stats.apt)));
+    for error in app.errors_for(Operation::Installed) {
+        lines.push(format_line_content(&format!(
+            "{}: {}",
+            error.source.display_name(),
+            error.error
+        )));
     }
-    if stats.flatpak > 0 {
-        println!("{}", format_line_content(&format!("Flatpak packages: {}", stats.flatpak)));
+
+    if stats.explicit + stats.dependency > 0 {
+        lines.push(format_line_content(&format!(
+            "Explicit: {}, dependencies: {}",
+            stats.explicit, stats.dependency
+        )));
     }
-    println!("|{}|", " ".repeat(inner_content_width + 2));
-    
+    if stats.installed_size > 0 {
+        lines.push(format_line_content(&format!(
+            "Installed size: {}",
+            format_size(stats.installed_size)
+        )));
+    }
+    lines.push(blank.clone());
+
     // "Outdated Packages" section
-    println!("{}", format_line_content("[Outdated Packages]"));
+    lines.push(format_line_content("[Outdated Packages]"));
+    let mut outdated_errors = app.errors_for(Operation::Outdated).peekable();
+    let failed = outdated_errors.peek().is_some();
+    for error in outdated_errors {
+        lines.push(format_line_content(&format!(
+            "{}: {}",
+            error.source.display_name(),
+            error.error
+        )));
+    }
     if !app.outdated_packages.is_empty() {
-        println!("{}", format_line_content(&format!("Total outdated: {}", stats.outdated)));
+        lines.push(format_line_content(&format!("Total outdated: {}", stats.outdated)));
+        for (manager, count) in &stats.outdated_by_source {
+            lines.push(format_line_content(&format!(
+                "{} outdated: {}",
+                manager.display_name(),
+                count
+            )));
+        }
         for package in app.outdated_packages.iter().take(5) {
-            println!("{}", format_line_content(&package.name));
+            lines.push(format_line_content(&format!(
+                "{} {}",
+                package.name,
+                format_version_change(package)
+            )));
         }
         if app.outdated_packages.len() > 5 {
-            println!("{}", format_line_content("..."));
+            lines.push(format_line_content("..."));
         }
-    } else {
-        println!("{}", format_line_content("All packages are up to date!"));
+    } else if !failed {
+        lines.push(format_line_content("All packages are up to date!"));
     }
-    
-    // Footer output
-    println!("+{}", "-".repeat(total_box_width - 2));
+
+    // Footer
+    lines.push(border);
+    lines
+}
+
+/// The enabled system panel rows that have a value, as `(label, value)`.
+pub fn system_rows(app: &App) -> Vec<(&'static str, String)> {
+    app.system_fields
+        .iter()
+        .filter_map(|field| Some((field.label(), app.system.field(*field)?)))
+        .collect()
+}
+
+/// Width of the terminal, falling back to `$COLUMNS` and then 80 columns
+/// when stdout is not a terminal.
+fn terminal_width() -> usize {
+    crossterm::terminal::size()
+        .ok()
+        .map(|(cols, _)| cols as usize)
+        .filter(|cols| *cols > 0)
+        .or_else(|| std::env::var("COLUMNS").ok()?.parse().ok())
+        .unwrap_or(80)
+}
+
+/// Clips `text` to `width` characters, marking the cut with an ellipsis.
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut clipped: String = text.chars().take(width.saturating_sub(1)).collect();
+    clipped.push('…');
+    clipped
 }
 
 pub fn render_tui_layout<B: Backend>(f: &mut Frame<B>, app: &App) {
@@ -110,48 +265,61 @@ pub fn render_tui_layout<B: Backend>(f: &mut Frame<B>, app: &App) {
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
         .split(chunks[1]);
 
-    // Left side - Package Statistics
-    let stats = app.get_package_stats();
-    let stats_text = vec![
-        Spans::from(vec![
-            Span::raw("Total packages: "),
-            Span::styled(format!("{}", stats.total), Style::default().fg(Color::Green)),
-        ]),
-        Spans::from(vec![
-            Span::raw("Pacman: "),
-            Span::styled(format!("{}", stats.pacman), Style::default().fg(Color::Blue)),
-        ]),
-        Spans::from(vec![
-            Span::raw("AUR: "),
-            Span::styled(format!("{}", stats.aur), Style::default().fg(Color::Yellow)),
-        ]),
-        Spans::from(vec![
-            Span::raw("Outdated: "),
-            Span::styled(format!("{}", stats.outdated), Style::default().fg(Color::Red)),
-        ]),
-    ];
+    // Left side - Logo above the package statistics
+    let logo_height = app.logo.as_ref().map_or(0, |logo| logo.height() as u16);
+    let left_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(logo_height), Constraint::Min(0)].as_ref())
+        .split(main_chunks[0]);
+    if let Some(logo) = &app.logo {
+        f.render_widget(Paragraph::new(logo.spans()), left_chunks[0]);
+    }
+
+    // Package Statistics
+    let stats = &app.stats;
+    let mut stats_text = vec![Spans::from(vec![
+        Span::raw("Total packages: "),
+        Span::styled(format!("{}", stats.total), Style::default().fg(Color::Green)),
+    ])];
+    for (manager, count) in &stats.by_source {
+        stats_text.push(Spans::from(vec![
+            Span::raw(format!("{}: ", manager.display_name())),
+            Span::styled(format!("{}", count), Style::default().fg(manager.color())),
+        ]));
+    }
+    stats_text.push(Spans::from(vec![
+        Span::raw("Outdated: "),
+        Span::styled(format!("{}", stats.outdated), Style::default().fg(Color::Red)),
+    ]));
 
     let stats_widget = Paragraph::new(stats_text)
         .block(Block::default().title("Statistics").borders(Borders::ALL));
-    f.render_widget(stats_widget, main_chunks[0]);
+    f.render_widget(stats_widget, left_chunks[1]);
 
     // Right side - Recent Packages
     let recent_items: Vec<ListItem> = app
-        .recent_packages
-        .iter()
-        .map(|pkg| {
+        .recent_events()
+        .map(|event| {
             ListItem::new(Spans::from(vec![
-                Span::raw(&pkg.name),
                 Span::styled(
-                    format!(" [{}]", pkg.source),
-                    Style::default().fg(Color::Gray),
+                    format!("{} ", event.action),
+                    Style::default().fg(event.action.color()),
+                ),
+                Span::raw(&event.name),
+                Span::styled(
+                    format!(" {}", format_event_versions(event)),
+                    Style::default().fg(Color::Green),
+                ),
+                Span::styled(
+                    format!(" [{}]", event.source.display_name()),
+                    Style::default().fg(event.source.color()),
                 ),
             ]))
         })
         .collect();
 
     let recent_widget = List::new(recent_items)
-        .block(Block::default().title("Recent Packages").borders(Borders::ALL));
+        .block(Block::default().title("Recent Activity").borders(Borders::ALL));
     f.render_widget(recent_widget, main_chunks[1]);
 
     // Footer
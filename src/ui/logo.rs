@@ -0,0 +1,306 @@
+//! Distro logos drawn next to the statistics.
+//!
+//! Logo lines may contain `$1` … `$9` markers that switch to the matching
+//! colour of the logo's palette for the rest of the line (or until the next
+//! marker), the same convention neofetch uses for its `${c1}` markers.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use tui::style::{Color, Style};
+use tui::text::{Span, Spans};
+
+use crate::fetch::system::Distro;
+
+#[derive(Debug, Clone)]
+pub struct Logo {
+    lines: Vec<String>,
+    colors: Vec<Color>,
+}
+
+impl Logo {
+    pub fn new(lines: Vec<String>, colors: Vec<Color>) -> Self {
+        Self { lines, colors }
+    }
+
+    /// Loads a custom logo from a text file.
+    ///
+    /// Colour markers in the file use the default palette: cyan, blue,
+    /// green, yellow, magenta, red, white, gray.
+    pub fn from_file(path: &Path) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        Ok(Self::new(
+            contents.lines().map(str::to_string).collect(),
+            DEFAULT_PALETTE.to_vec(),
+        ))
+    }
+
+    /// The built-in logo with this name (an `os-release` ID), if any.
+    pub fn builtin(name: &str) -> Option<Self> {
+        let (_, art, colors) = BUILTIN
+            .iter()
+            .find(|(ids, _, _)| ids.contains(&name.to_lowercase().as_str()))?;
+        Some(Self::new(
+            art.iter().map(|l| l.to_string()).collect(),
+            colors.to_vec(),
+        ))
+    }
+
+    /// The built-in logo for a distro, matched by `ID` and then by each
+    /// `ID_LIKE` entry. Unknown distros get Tux.
+    pub fn for_distro(distro: &Distro) -> Self {
+        distro
+            .id
+            .iter()
+            .chain(&distro.id_like)
+            .find_map(|id| Self::builtin(id))
+            .unwrap_or_else(|| Self::builtin("linux").expect("tux logo is built in"))
+    }
+
+    pub fn height(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Width in columns, ignoring colour markers.
+    pub fn width(&self) -> usize {
+        (0..self.height())
+            .map(|i| self.plain_line(i).chars().count())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Splits a line into runs of text and the colour they are drawn in.
+    pub fn segments(&self, index: usize) -> Vec<(Option<Color>, String)> {
+        let Some(line) = self.lines.get(index) else {
+            return Vec::new();
+        };
+
+        // Colour carries over from the previous line, like in neofetch
+        let mut color = self.colors.first().copied();
+        for previous in &self.lines[..index] {
+            if let Some(last) = markers(previous).last() {
+                color = self.color(*last);
+            }
+        }
+
+        let mut segments = Vec::new();
+        let mut text = String::new();
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c == '$' {
+                if let Some(digit) = chars.peek().and_then(|d| d.to_digit(10)) {
+                    chars.next();
+                    if !text.is_empty() {
+                        segments.push((color, std::mem::take(&mut text)));
+                    }
+                    color = self.color(digit as usize);
+                    continue;
+                }
+            }
+            text.push(c);
+        }
+        if !text.is_empty() {
+            segments.push((color, text));
+        }
+        segments
+    }
+
+    /// A line without colour markers.
+    pub fn plain_line(&self, index: usize) -> String {
+        self.segments(index).into_iter().map(|(_, text)| text).collect()
+    }
+
+    /// A line with ANSI colour escapes, for printing to a terminal.
+    pub fn ansi_line(&self, index: usize) -> String {
+        let mut line = String::new();
+        for (color, text) in self.segments(index) {
+            match color.and_then(ansi_code) {
+                Some(code) => line.push_str(&format!("\x1b[1;{}m{}\x1b[0m", code, text)),
+                None => line.push_str(&text),
+            }
+        }
+        line
+    }
+
+    /// The logo as styled TUI lines.
+    pub fn spans(&self) -> Vec<Spans<'static>> {
+        (0..self.height())
+            .map(|i| {
+                Spans::from(
+                    self.segments(i)
+                        .into_iter()
+                        .map(|(color, text)| match color {
+                            Some(color) => Span::styled(text, Style::default().fg(color)),
+                            None => Span::raw(text),
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect()
+    }
+
+    /// Palette lookup for a 1-based marker.
+    fn color(&self, marker: usize) -> Option<Color> {
+        marker.checked_sub(1).and_then(|i| self.colors.get(i)).copied()
+    }
+}
+
+/// The colour markers used in a line, in order.
+fn markers(line: &str) -> Vec<usize> {
+    let mut found = Vec::new();
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '$' {
+            if let Some(digit) = chars.peek().and_then(|d| d.to_digit(10)) {
+                chars.next();
+                found.push(digit as usize);
+            }
+        }
+    }
+    found
+}
+
+/// SGR foreground code for the basic terminal colours.
+fn ansi_code(color: Color) -> Option<u8> {
+    Some(match color {
+        Color::Black => 30,
+        Color::Red => 31,
+        Color::Green => 32,
+        Color::Yellow => 33,
+        Color::Blue => 34,
+        Color::Magenta => 35,
+        Color::Cyan => 36,
+        Color::Gray => 37,
+        Color::DarkGray => 90,
+        Color::LightRed => 91,
+        Color::LightGreen => 92,
+        Color::LightYellow => 93,
+        Color::LightBlue => 94,
+        Color::LightMagenta => 95,
+        Color::LightCyan => 96,
+        Color::White => 97,
+        _ => return None,
+    })
+}
+
+const DEFAULT_PALETTE: [Color; 8] = [
+    Color::Cyan,
+    Color::Blue,
+    Color::Green,
+    Color::Yellow,
+    Color::Magenta,
+    Color::Red,
+    Color::White,
+    Color::Gray,
+];
+
+type BuiltinLogo = (&'static [&'static str], &'static [&'static str], &'static [Color]);
+
+/// Built-in logos: the `os-release` IDs each one is used for, the art and
+/// its palette. Derivatives come before their base so that `ID` wins over
+/// `ID_LIKE`.
+const BUILTIN: &[BuiltinLogo] = &[
+    (&["manjaro"], MANJARO, &[Color::Green]),
+    (
+        &["endeavouros"],
+        ENDEAVOUROS,
+        &[Color::Red, Color::Magenta, Color::Blue],
+    ),
+    (&["arch", "archarm", "artix", "garuda", "cachyos"], ARCH, &[Color::Cyan]),
+    (&["linuxmint"], MINT, &[Color::Green, Color::White]),
+    (&["pop"], POP, &[Color::Cyan]),
+    (&["ubuntu"], UBUNTU, &[Color::Red]),
+    (&["debian", "raspbian"], DEBIAN, &[Color::Red]),
+    (&["fedora", "nobara", "ultramarine"], FEDORA, &[Color::Blue, Color::White]),
+    (&["linux"], TUX, &[Color::White, Color::DarkGray, Color::Yellow]),
+];
+
+const ARCH: &[&str] = &[
+    r"$1      /\",
+    r"     /  \",
+    r"    /\   \",
+    r"   /      \",
+    r"  /   ,,   \",
+    r" /   |  |  -\",
+    r"/_-''    ''-_\",
+];
+
+const MANJARO: &[&str] = &[
+    r"$1||||||||| ||||",
+    r"||||||||| ||||",
+    r"||||      ||||",
+    r"|||| |||| ||||",
+    r"|||| |||| ||||",
+    r"|||| |||| ||||",
+    r"|||| |||| ||||",
+];
+
+const ENDEAVOUROS: &[&str] = &[
+    r"$1      /$2\",
+    r"$1    //$2  \$3\",
+    r"$1   //$2    \ $3\",
+    r"$1 //$2    _)  $3)",
+    r"$1/$2_/___-- __-",
+    r"$3 /____--",
+];
+
+const DEBIAN: &[&str] = &[
+    r"$1  _____",
+    r" /  __ \",
+    r"|  /    |",
+    r"|  \___-",
+    r"-_",
+    r"  --_",
+];
+
+const UBUNTU: &[&str] = &[
+    r"$1         _",
+    r"     ---(_)",
+    r" _/  ---  \",
+    r"(_) |   |",
+    r"  \  --- _/",
+    r"     ---(_)",
+];
+
+const MINT: &[&str] = &[
+    r"$1 _____________",
+    r"|_            \",
+    r"  | $2| _____ $1|",
+    r"  | $2| | | | $1|",
+    r"  | $2| | | | $1|",
+    r"  | $2\_____/ $1|",
+    r"  \_________/",
+];
+
+const POP: &[&str] = &[
+    r"$1______",
+    r"\   _ \        __",
+    r" \ \ \ \      / /",
+    r"  \ \_\ \    / /",
+    r"   \  ___\  /_/",
+    r"    \ \    _",
+    r"   __\_\__(_)_",
+    r"  (___________)",
+];
+
+const FEDORA: &[&str] = &[
+    r"$1      _____",
+    r"     /   __)$2\",
+    r"$1     |  /  \ $2\",
+    r"$1  ___|  |__/ $2/",
+    r"$1 / (_    _)$2_/",
+    r"$1/ /  |  |",
+    r"\ \__/  |",
+    r" \(_____/",
+];
+
+const TUX: &[&str] = &[
+    r"$2    .--.",
+    r"   |$1o$2_$1o $2|",
+    r"   |$3:_/ $2|",
+    r"  //   \ \",
+    r" (|     | )",
+    r"$3/'\_   _/`\",
+    r"\___)=(___/",
+];
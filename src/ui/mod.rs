@@ -1,4 +1,6 @@
 pub mod layout;
+pub mod logo;
+pub mod timeline;
 pub mod widgets;
 pub mod tui_app;
 
@@ -0,0 +1,271 @@
+use crossterm::event::KeyCode;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+use crate::fetch::events::{parse_date, EventFilter};
+use crate::fetch::{EventAction, PackageEvent};
+use crate::utils::{format_event_versions, format_timestamp};
+
+/// Key help shown in the footer while the timeline is open.
+const HELP: &str = "↑↓/PgUp/PgDn scroll, 'a' action, 's' source, 'f'/'u' from/until, \
+'g' go to date, 'c' clear, 't' back";
+
+/// Key help shown while a date is being typed.
+const PROMPT_HELP: &str = "Enter to apply, Esc to cancel";
+
+/// What a date typed into the prompt is used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PromptKind {
+    Jump,
+    Since,
+    Until,
+}
+
+impl PromptKind {
+    fn label(self) -> &'static str {
+        match self {
+            PromptKind::Jump => "Go to date",
+            PromptKind::Since => "Show from",
+            PromptKind::Until => "Show until",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Prompt {
+    kind: PromptKind,
+    input: String,
+    invalid: bool, // the last Enter did not parse
+}
+
+/// The chronological list of package events from every source, with its
+/// scroll position, filter and date prompt.
+#[derive(Debug, Clone, Default)]
+pub struct Timeline {
+    pub filter: EventFilter,
+    selected: Option<usize>, // index into the filtered events; None follows the newest
+    page: usize,             // rows visible in the last draw
+    prompt: Option<Prompt>,
+    list: ListState,
+}
+
+impl Timeline {
+    /// The events that pass the filter, oldest first.
+    fn visible<'a>(&self, app: &'a App) -> Vec<&'a PackageEvent> {
+        app.events
+            .iter()
+            .filter(|event| self.filter.matches(event))
+            .collect()
+    }
+
+    /// Key help for the footer.
+    pub fn help(&self) -> &'static str {
+        if self.prompt.is_some() {
+            PROMPT_HELP
+        } else {
+            HELP
+        }
+    }
+
+    /// Handles a key press, returning false if the timeline has no use
+    /// for it. While a prompt is open every key goes to the prompt.
+    pub fn handle_key(&mut self, key: KeyCode, app: &App) -> bool {
+        if self.prompt.is_some() {
+            self.handle_prompt_key(key, app);
+            return true;
+        }
+
+        let count = self.visible(app).len();
+        let current = self.selected.unwrap_or(count.saturating_sub(1)).min(count.saturating_sub(1));
+        let page = self.page.max(1);
+        match key {
+            KeyCode::Up | KeyCode::Char('k') => self.select(current.saturating_sub(1), count),
+            KeyCode::Down | KeyCode::Char('j') => self.select(current + 1, count),
+            KeyCode::PageUp => self.select(current.saturating_sub(page), count),
+            KeyCode::PageDown => self.select(current + page, count),
+            KeyCode::Home => self.select(0, count),
+            KeyCode::End => self.selected = None,
+            KeyCode::Char('a') => {
+                self.filter.actions = cycle(&self.filter.actions, &EventAction::ALL);
+                self.selected = None;
+            }
+            KeyCode::Char('s') => {
+                self.filter.sources = cycle(&self.filter.sources, &app.profile.managers);
+                self.selected = None;
+            }
+            KeyCode::Char('f') => self.open_prompt(PromptKind::Since),
+            KeyCode::Char('u') => self.open_prompt(PromptKind::Until),
+            KeyCode::Char('g') => self.open_prompt(PromptKind::Jump),
+            KeyCode::Char('c') => {
+                self.filter = EventFilter::default();
+                self.selected = None;
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Selects row `index`; the last row means following the newest event.
+    fn select(&mut self, index: usize, count: usize) {
+        self.selected = if index + 1 >= count { None } else { Some(index) };
+    }
+
+    fn open_prompt(&mut self, kind: PromptKind) {
+        self.prompt = Some(Prompt {
+            kind,
+            input: String::new(),
+            invalid: false,
+        });
+    }
+
+    fn handle_prompt_key(&mut self, key: KeyCode, app: &App) {
+        let Some(prompt) = &mut self.prompt else {
+            return;
+        };
+        match key {
+            KeyCode::Char(c) if c.is_ascii_digit() || c == '-' => prompt.input.push(c),
+            KeyCode::Backspace => {
+                prompt.input.pop();
+            }
+            KeyCode::Esc => self.prompt = None,
+            KeyCode::Enter => {
+                let kind = prompt.kind;
+                let date = parse_date(&prompt.input);
+                // An empty from/until prompt removes that bound
+                if date.is_none() && !(prompt.input.is_empty() && kind != PromptKind::Jump) {
+                    prompt.invalid = true;
+                    return;
+                }
+                self.prompt = None;
+                match kind {
+                    PromptKind::Since => self.filter.since = date,
+                    PromptKind::Until => self.filter.until = date.map(|day| day + 86_400),
+                    PromptKind::Jump => {
+                        let events = self.visible(app);
+                        let day = date.unwrap_or_default();
+                        let index = events.partition_point(|event| event.timestamp < day);
+                        self.select(index, events.len());
+                        return;
+                    }
+                }
+                self.selected = None;
+            }
+            _ => {}
+        }
+    }
+
+    pub fn draw<B: Backend>(&mut self, f: &mut Frame<B>, app: &App, title: Spans<'static>, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                [
+                    Constraint::Min(3),
+                    Constraint::Length(if self.prompt.is_some() { 3 } else { 0 }),
+                ]
+                .as_ref(),
+            )
+            .split(area);
+
+        let events = self.visible(app);
+        let items: Vec<ListItem> = events
+            .iter()
+            .map(|event| {
+                let mut spans = vec![
+                    Span::styled(
+                        format!("{} ", format_timestamp(event.timestamp)),
+                        Style::default().fg(Color::Gray),
+                    ),
+                    Span::styled(
+                        format!("{:<11} ", event.action.as_str()),
+                        Style::default().fg(event.action.color()),
+                    ),
+                    Span::raw(event.name.clone()),
+                    Span::styled(
+                        format!(" {}", format_event_versions(event)),
+                        Style::default().fg(Color::Green),
+                    ),
+                    Span::styled(
+                        format!(" [{}]", event.source.display_name()),
+                        Style::default().fg(event.source.color()),
+                    ),
+                ];
+                if let Some(command) = &event.command {
+                    spans.push(Span::styled(
+                        format!("  $ {}", command),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+                ListItem::new(Spans::from(spans))
+            })
+            .collect();
+
+        let mut title = title;
+        title.0.push(Span::styled(
+            format!(" {} of {} events", events.len(), app.events.len()),
+            Style::default().fg(Color::Gray),
+        ));
+        if !self.filter.is_empty() {
+            title.0.push(Span::styled(
+                format!(" ({})", self.describe_filter()),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+
+        self.page = chunks[0].height.saturating_sub(2) as usize;
+        self.list.select(
+            self.selected
+                .map(|i| i.min(events.len().saturating_sub(1)))
+                .or(events.len().checked_sub(1)),
+        );
+        let list = List::new(items)
+            .block(Block::default().title(title).borders(Borders::ALL))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        f.render_stateful_widget(list, chunks[0], &mut self.list);
+
+        if let Some(prompt) = &self.prompt {
+            let mut spans = vec![
+                Span::raw(format!("{} (YYYY-MM-DD): ", prompt.kind.label())),
+                Span::styled(prompt.input.clone(), Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw("_"),
+            ];
+            if prompt.invalid {
+                spans.push(Span::styled("  invalid date", Style::default().fg(Color::Red)));
+            }
+            let widget = Paragraph::new(Spans::from(spans))
+                .block(Block::default().borders(Borders::ALL));
+            f.render_widget(widget, chunks[1]);
+        }
+    }
+
+    /// A short description of the active filter, e.g.
+    /// `upgraded, apt, from 2024-01-01`.
+    fn describe_filter(&self) -> String {
+        let mut parts: Vec<String> = Vec::new();
+        parts.extend(self.filter.actions.iter().map(|a| a.as_str().to_string()));
+        parts.extend(self.filter.sources.iter().map(|s| s.as_str().to_string()));
+        if let Some(since) = self.filter.since {
+            parts.push(format!("from {}", &format_timestamp(since)[..10]));
+        }
+        if let Some(until) = self.filter.until {
+            parts.push(format!("until {}", &format_timestamp(until - 86_400)[..10]));
+        }
+        parts.join(", ")
+    }
+}
+
+/// Steps a single-value filter through `options`, starting and ending with
+/// no filter at all.
+fn cycle<T: Copy + PartialEq>(current: &[T], options: &[T]) -> Vec<T> {
+    let next = match current {
+        [one] => options.iter().position(|o| o == one).map_or(0, |i| i + 1),
+        _ => 0,
+    };
+    options.get(next).map(|o| vec![*o]).unwrap_or_default()
+}
@@ -9,45 +9,140 @@ use std::{
 };
 use tui::{
     backend::{Backend, CrosstermBackend},
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Span, Spans},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
     Frame, Terminal,
 };
 
 use crate::app::App;
-use crate::fetch::{detect_system, format_size};
+use crate::fetch::collector::Panel;
+use crate::fetch::error::{Operation, Severity};
+use crate::ui::layout::system_rows;
+use crate::ui::timeline::Timeline;
+use crate::ui::widgets::PackageGraph;
+use crate::fetch::growth::{self, Period};
+use crate::fetch::{format_size, PackageEvent, PackageManager};
+use crate::utils::{
+    format_event_versions, format_relative_time, format_version_change, unix_now,
+};
+
+/// Frames of the loading indicator shown in panel titles.
+const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// The screen shown in the main area.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum View {
+    Dashboard,
+    Diagnostics,
+    Timeline,
+    Growth,
+}
+
+/// UI state that is not part of the collected data.
+struct TuiState {
+    view: View,
+    frame: usize, // spinner animation frame
+    timeline: Timeline,
+    period: Period, // step of the growth graph
+}
 
 pub fn run_tui<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> io::Result<()> {
     let mut last_tick = Instant::now();
     let tick_rate = Duration::from_millis(250);
+    let mut state = TuiState {
+        view: View::Dashboard,
+        frame: 0,
+        timeline: Timeline::default(),
+        period: Period::default(),
+    };
+    let mut redraw = true;
 
     loop {
-        terminal.draw(|f| ui(f, &app))?;
+        // Only draw when something changed; an idle app does no work
+        redraw |= app.poll();
+        if redraw {
+            terminal.draw(|f| ui(f, &app, &mut state))?;
+            redraw = false;
+        }
 
         let timeout = tick_rate
             .checked_sub(last_tick.elapsed())
             .unwrap_or_else(|| Duration::from_secs(0));
 
         if crossterm::event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                match key.code {
+            match event::read()? {
+                // The timeline gets first pick, so its prompt can take any key
+                Event::Key(key)
+                    if state.view == View::Timeline && state.timeline.handle_key(key.code, &app) =>
+                {
+                    redraw = true;
+                }
+                Event::Key(key) => match key.code {
                     KeyCode::Char('q') => return Ok(()),
-                    KeyCode::Char('r') => app.update(),
+                    KeyCode::Char('r') => {
+                        app.refresh();
+                        redraw = true;
+                    }
+                    KeyCode::Char('d') => {
+                        state.view = match state.view {
+                            View::Diagnostics => View::Dashboard,
+                            _ => View::Diagnostics,
+                        };
+                        redraw = true;
+                    }
+                    KeyCode::Char('t') => {
+                        state.view = match state.view {
+                            View::Timeline => View::Dashboard,
+                            _ => View::Timeline,
+                        };
+                        redraw = true;
+                    }
+                    KeyCode::Char('g') => {
+                        state.view = match state.view {
+                            View::Growth => View::Dashboard,
+                            _ => View::Growth,
+                        };
+                        redraw = true;
+                    }
+                    KeyCode::Char('p') if state.view == View::Growth => {
+                        state.period = state.period.next();
+                        redraw = true;
+                    }
                     KeyCode::Esc => return Ok(()),
                     _ => {}
-                }
+                },
+                Event::Resize(..) => redraw = true,
+                _ => {}
             }
         }
 
         if last_tick.elapsed() >= tick_rate {
             last_tick = Instant::now();
+            // Keep the spinners moving
+            if app.is_refreshing() {
+                state.frame = state.frame.wrapping_add(1);
+                redraw = true;
+            }
         }
     }
 }
 
-fn ui<B: Backend>(f: &mut Frame<B>, app: &App) {
+/// A panel title, followed by a spinner while the panel is still loading.
+fn panel_title(app: &App, panels: &[Panel], title: &'static str, style: Style, frame: usize) -> Spans<'static> {
+    let mut spans = vec![Span::styled(title, style)];
+    if panels.iter().any(|panel| app.is_loading(*panel)) {
+        spans.push(Span::styled(
+            format!(" {}", SPINNER[frame % SPINNER.len()]),
+            Style::default().fg(Color::Gray),
+        ));
+    }
+    Spans::from(spans)
+}
+
+/// Draws the whole screen from `app` state; nothing is collected here.
+fn ui<B: Backend>(f: &mut Frame<B>, app: &App, state: &mut TuiState) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .margin(1)
@@ -72,14 +167,126 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &App) {
         .block(Block::default().borders(Borders::ALL));
     f.render_widget(title, chunks[0]);
 
+    match state.view {
+        View::Dashboard => draw_dashboard(f, app, state.frame, chunks[1]),
+        View::Diagnostics => draw_diagnostics(f, app, state.frame, chunks[1]),
+        View::Timeline => {
+            let title = panel_title(
+                app,
+                &[Panel::Recent],
+                "Timeline",
+                Style::default().fg(Color::Blue).add_modifier(Modifier::BOLD),
+                state.frame,
+            );
+            state.timeline.draw(f, app, title, chunks[1]);
+        }
+        View::Growth => draw_growth(f, app, state, chunks[1]),
+    }
+
+    // Footer, pointing at the diagnostics when something failed
+    let mut footer = vec![Span::raw(match state.view {
+        View::Timeline => state.timeline.help(),
+        View::Growth => "'p' day/week/month, 'g' back, 'q' to quit",
+        _ => "Press 'q' to quit, 'r' to refresh, 'd' for diagnostics, 't' for the timeline, 'g' for growth",
+    })];
+    for severity in [Severity::Error, Severity::Warning] {
+        let count = app.error_count(severity);
+        if count > 0 {
+            footer.push(Span::styled(
+                format!(" ({} {}{})", count, severity.as_str(), if count == 1 { "" } else { "s" }),
+                severity_style(severity),
+            ));
+        }
+    }
+    let footer = Paragraph::new(Spans::from(footer))
+        .style(Style::default().fg(Color::Gray))
+        .alignment(tui::layout::Alignment::Center);
+    f.render_widget(footer, chunks[2]);
+}
+
+/// Draws the installed package count over time, replayed from the history.
+fn draw_growth<B: Backend>(f: &mut Frame<B>, app: &App, state: &TuiState, area: Rect) {
+    let mut title = panel_title(
+        app,
+        &[Panel::Packages, Panel::Recent],
+        "Package Growth",
+        Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        state.frame,
+    );
+    title.0.push(Span::styled(
+        format!(" per {}", state.period.as_str()),
+        Style::default().fg(Color::Gray),
+    ));
+
+    let points = growth::replay(&app.events, app.stats.total, state.period, unix_now());
+    PackageGraph::new(points, state.period).render(f, area, title);
+}
+
+fn draw_dashboard<B: Backend>(f: &mut Frame<B>, app: &App, frame: usize, area: Rect) {
     // Main content - Left side single, Right side split
     let main_chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(40), Constraint::Percentage(60)].as_ref())
-        .split(chunks[1]);
+        .split(area);
 
-    // Left side - Extended Package Statistics
-    let stats = app.get_package_stats();
+    // Left side - Logo and system info on top of the package statistics
+    let system_rows = system_rows(app);
+    let logo_height = app.logo.as_ref().map_or(0, |logo| logo.height());
+    let left_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length(if system_rows.is_empty() && logo_height == 0 {
+                    0
+                } else {
+                    system_rows.len().max(logo_height) as u16 + 2
+                }),
+                Constraint::Min(10),
+            ]
+            .as_ref(),
+        )
+        .split(main_chunks[0]);
+
+    let system_text: Vec<Spans> = system_rows
+        .into_iter()
+        .map(|(label, value)| {
+            Spans::from(vec![
+                Span::styled(
+                    format!("{}: ", label),
+                    Style::default()
+                        .fg(Color::Blue)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(value),
+            ])
+        })
+        .collect();
+    let system_block = Block::default()
+        .title(panel_title(
+            app,
+            &[Panel::System],
+            "System",
+            Style::default()
+                .fg(Color::Blue)
+                .add_modifier(Modifier::BOLD),
+            frame,
+        ))
+        .borders(Borders::ALL);
+    let system_area = system_block.inner(left_chunks[0]);
+    f.render_widget(system_block, left_chunks[0]);
+
+    let logo_width = app.logo.as_ref().map_or(0, |logo| logo.width() as u16 + 2);
+    let system_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(logo_width), Constraint::Min(0)].as_ref())
+        .split(system_area);
+    if let Some(logo) = &app.logo {
+        f.render_widget(Paragraph::new(logo.spans()), system_chunks[0]);
+    }
+    f.render_widget(Paragraph::new(system_text), system_chunks[1]);
+
+    // Package Statistics
+    let stats = &app.stats;
     let uptodate_packages = stats.total.saturating_sub(stats.outdated);
     let outdated_percentage = if stats.total > 0 {
         (stats.outdated as f64 / stats.total as f64) * 100.0
@@ -87,24 +294,6 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &App) {
         0.0
     };
 
-    // Calculate additional statistics
-    let pacman_percentage = if stats.total > 0 {
-        (stats.pacman as f64 / stats.total as f64) * 100.0
-    } else {
-        0.0
-    };
-    let aur_percentage = if stats.total > 0 {
-        (stats.aur as f64 / stats.total as f64) * 100.0
-    } else {
-        0.0
-    };
-
-    // Detect system type
-    let system_commands = detect_system();
-    let is_arch = system_commands.iter().any(|(cmd, _)| *cmd == "pacman");
-    let is_debian = system_commands.iter().any(|(cmd, _)| *cmd == "dpkg");
-    let is_fedora = system_commands.iter().any(|(cmd, _)| *cmd == "rpm");
-
     let mut stats_text = vec![
         Spans::from(vec![
             Span::raw("Total packages: "),
@@ -118,56 +307,60 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &App) {
         Spans::from(""),
     ];
 
-    // Show package types based on detected system
-    if is_arch {
+    // Show every source that has packages, with its share of the total
+    for (manager, count) in &stats.by_source {
+        let percentage = (*count as f64 / stats.total as f64) * 100.0;
         stats_text.push(Spans::from(vec![
-            Span::raw("Pacman: "),
-            Span::styled(
-                format!("{}", stats.pacman),
-                Style::default().fg(Color::Blue),
-            ),
-            Span::raw(" ("),
             Span::styled(
-                format!("{:.1}%", pacman_percentage),
-                Style::default().fg(Color::Gray),
+                format!("{} ", manager.icon()),
+                Style::default().fg(manager.color()),
             ),
-            Span::raw(")"),
-        ]));
-        stats_text.push(Spans::from(vec![
-            Span::raw("AUR: "),
-            Span::styled(format!("{}", stats.aur), Style::default().fg(Color::Yellow)),
+            Span::raw(format!("{}: ", manager.display_name())),
+            Span::styled(format!("{}", count), Style::default().fg(manager.color())),
             Span::raw(" ("),
             Span::styled(
-                format!("{:.1}%", aur_percentage),
+                format!("{:.1}%", percentage),
                 Style::default().fg(Color::Gray),
             ),
             Span::raw(")"),
         ]));
+
+        // Split the Flatpak count into apps and runtimes
+        if *manager == PackageManager::Flatpak && stats.applications + stats.runtimes > 0 {
+            let mut spans = vec![Span::raw(format!(
+                "  {} apps, {} runtimes",
+                stats.applications, stats.runtimes
+            ))];
+            if stats.unused_runtimes > 0 {
+                spans.push(Span::styled(
+                    format!(" ({} unused)", stats.unused_runtimes),
+                    Style::default().fg(Color::Yellow),
+                ));
+            }
+            stats_text.push(Spans::from(spans));
+        }
     }
 
-    if is_debian {
+    if stats.explicit + stats.dependency > 0 {
         stats_text.push(Spans::from(vec![
-            Span::raw("APT: "),
+            Span::raw("Explicit: "),
             Span::styled(
-                format!("{}", stats.apt),
-                Style::default().fg(Color::Magenta),
+                format!("{}", stats.explicit),
+                Style::default().fg(Color::Green),
+            ),
+            Span::raw(", dependencies: "),
+            Span::styled(
+                format!("{}", stats.dependency),
+                Style::default().fg(Color::Gray),
             ),
         ]));
     }
 
-    if is_fedora {
-        stats_text.push(Spans::from(vec![
-            Span::raw("DNF: "),
-            Span::styled(format!("{}", stats.dnf), Style::default().fg(Color::Red)),
-        ]));
-    }
-
-    // Show Flatpak only if it's available
-    if stats.flatpak > 0 {
+    if stats.installed_size > 0 {
         stats_text.push(Spans::from(vec![
-            Span::raw("Flatpak: "),
+            Span::raw("Installed size: "),
             Span::styled(
-                format!("{}", stats.flatpak),
+                format_size(stats.installed_size),
                 Style::default().fg(Color::Cyan),
             ),
         ]));
@@ -182,13 +375,23 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &App) {
                 Style::default().fg(Color::Green),
             ),
         ]),
-        Spans::from(vec![
-            Span::raw("Outdated: "),
-            Span::styled(
-                format!("{}", stats.outdated),
-                Style::default().fg(Color::Red),
-            ),
-        ]),
+        Spans::from(
+            [
+                Span::raw("Outdated: "),
+                Span::styled(
+                    format!("{}", stats.outdated),
+                    Style::default().fg(Color::Red),
+                ),
+            ]
+            .into_iter()
+            .chain(stats.outdated_by_source.iter().map(|(manager, count)| {
+                Span::styled(
+                    format!(" {}{}", manager.icon(), count),
+                    Style::default().fg(manager.color()),
+                )
+            }))
+            .collect::<Vec<_>>(),
+        ),
         Spans::from(""),
         Spans::from(vec![
             Span::raw("System health: "),
@@ -203,26 +406,6 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &App) {
                 }),
             ),
         ]),
-        Spans::from(""),
-        Spans::from(vec![
-            Span::raw("System: "),
-            Span::styled(
-                if is_arch {
-                    "Arch Linux"
-                } else if is_debian {
-                    "Debian/Ubuntu"
-                } else if is_fedora {
-                    "Fedora Linux"
-                } else {
-                    "Unknown"
-                },
-                Style::default().fg(Color::Blue),
-            ),
-        ]),
-        Spans::from(vec![
-            Span::raw("Kernel: "),
-            Span::styled("6.16.7-zen1-1-zen", Style::default().fg(Color::Gray)),
-        ]),
     ]);
 
     // Add separator
@@ -261,20 +444,27 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &App) {
                 format_size(disk.used),
                 format_size(disk.total)
             )),
+            Span::styled(
+                format!(" {}", disk.fs_type),
+                Style::default().fg(Color::Gray),
+            ),
         ]));
     }
 
     let stats_widget = Paragraph::new(stats_text).block(
         Block::default()
-            .title(Spans::from(vec![Span::styled(
+            .title(panel_title(
+                app,
+                &[Panel::Packages, Panel::Outdated, Panel::Disks],
                 "Package Statistics",
                 Style::default()
                     .fg(Color::Green)
                     .add_modifier(Modifier::BOLD),
-            )]))
+                frame,
+            ))
             .borders(Borders::ALL),
     );
-    f.render_widget(stats_widget, main_chunks[0]);
+    f.render_widget(stats_widget, left_chunks[1]);
 
     // Right side - Split into two parts
     let right_chunks = Layout::default()
@@ -283,12 +473,13 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &App) {
         .split(main_chunks[1]);
 
     // Top right - Outdated Packages
-    let outdated_items: Vec<ListItem> = if let Some(err) = &app.error_message {
+    let mut outdated_items = error_items(app, Operation::Outdated);
+    outdated_items.extend(if app.outdated_packages.is_empty() && app.is_loading(Panel::Outdated) {
         vec![ListItem::new(Spans::from(vec![Span::styled(
-            err.clone(),
-            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            "Checking for updates...",
+            Style::default().fg(Color::Gray),
         )]))]
-    } else if app.outdated_packages.is_empty() {
+    } else if app.outdated_packages.is_empty() && outdated_items.is_empty() {
         vec![ListItem::new(Spans::from(vec![Span::styled(
             "All packages are up to date!",
             Style::default().fg(Color::Green),
@@ -301,62 +492,200 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &App) {
                 ListItem::new(Spans::from(vec![
                     Span::raw(&pkg.name),
                     Span::styled(
-                        format!(" [{}]", pkg.source),
+                        format!(" {}", format_version_change(pkg)),
+                        Style::default().fg(Color::Yellow),
+                    ),
+                    Span::styled(
+                        format!(" [{}]", pkg.source.display_name()),
+                        Style::default().fg(pkg.source.color()),
+                    ),
+                    Span::styled(
+                        pkg.repo.as_ref().map(|repo| format!(" {}", repo)).unwrap_or_default(),
                         Style::default().fg(Color::Gray),
                     ),
                 ]))
             })
             .collect()
-    };
+    });
 
     let outdated_widget = List::new(outdated_items).block(
         Block::default()
-            .title(Spans::from(vec![Span::styled(
+            .title(panel_title(
+                app,
+                &[Panel::Outdated],
                 "Outdated Packages",
                 Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
-            )]))
+                frame,
+            ))
             .borders(Borders::ALL),
     );
     f.render_widget(outdated_widget, right_chunks[0]);
 
     // Bottom right - Recent Packages
-    let recent_items: Vec<ListItem> = if app.recent_packages.is_empty() {
+    let mut recent_items = error_items(app, Operation::History);
+    recent_items.extend(if app.events.is_empty() && app.is_loading(Panel::Recent) {
+        vec![ListItem::new(Spans::from(vec![Span::styled(
+            "Loading...",
+            Style::default().fg(Color::Gray),
+        )]))]
+    } else if app.events.is_empty() && recent_items.is_empty() {
         vec![ListItem::new(Spans::from(vec![Span::styled(
-            "No recent packages found",
+            "No recent activity found",
             Style::default().fg(Color::Gray),
         )]))]
     } else {
-        app.recent_packages
-            .iter()
-            .map(|pkg| {
-                ListItem::new(Spans::from(vec![
-                    Span::raw(&pkg.name),
-                    Span::styled(
-                        format!(" [{}]", pkg.source),
-                        Style::default().fg(Color::Gray),
-                    ),
-                ]))
-            })
-            .collect()
-    };
+        let now = unix_now();
+        let mut items = Vec::new();
+        let mut previous: Option<&PackageEvent> = None;
+        for event in app.recent_events() {
+            // Head each transaction with the command that started it
+            if let Some(command) = &event.command {
+                if !previous.is_some_and(|p| p.same_transaction(event)) {
+                    items.push(ListItem::new(Spans::from(vec![Span::styled(
+                        format!("$ {}", command),
+                        Style::default().fg(Color::Gray).add_modifier(Modifier::ITALIC),
+                    )])));
+                }
+            }
+            previous = Some(event);
+            items.push(ListItem::new(Spans::from(vec![
+                Span::styled(
+                    format!("{:<11} ", event.action.as_str()),
+                    Style::default().fg(event.action.color()),
+                ),
+                Span::raw(&event.name),
+                Span::styled(
+                    format!(" {}", format_event_versions(event)),
+                    Style::default().fg(Color::Green),
+                ),
+                Span::styled(
+                    format!(" {}", format_relative_time(event.timestamp, now)),
+                    Style::default().fg(Color::Gray),
+                ),
+                Span::styled(
+                    format!(" [{}]", event.source.display_name()),
+                    Style::default().fg(event.source.color()),
+                ),
+            ])));
+        }
+        items
+    });
 
     let recent_widget = List::new(recent_items).block(
         Block::default()
-            .title(Spans::from(vec![Span::styled(
-                "Recent Packages",
+            .title(panel_title(
+                app,
+                &[Panel::Recent],
+                "Recent Activity",
                 Style::default()
                     .fg(Color::Blue)
                     .add_modifier(Modifier::BOLD),
-            )]))
+                frame,
+            ))
             .borders(Borders::ALL),
     );
     f.render_widget(recent_widget, right_chunks[1]);
+}
 
-    // Footer
-    let footer = Paragraph::new("Press 'q' to quit, 'r' to refresh")
-        .style(Style::default().fg(Color::Gray))
-        .alignment(tui::layout::Alignment::Center);
-    f.render_widget(footer, chunks[2]);
+/// One row per problem `operation` hit, for the top of a list: red for
+/// errors, yellow for warnings.
+fn error_items(app: &App, operation: Operation) -> Vec<ListItem<'static>> {
+    app.errors_for(operation)
+        .map(|error| {
+            ListItem::new(Spans::from(vec![
+                Span::styled(
+                    format!("{} ", error.source.icon()),
+                    Style::default().fg(error.source.color()),
+                ),
+                Span::styled(
+                    error.error.to_string(),
+                    severity_style(error.severity).add_modifier(Modifier::BOLD),
+                ),
+            ]))
+        })
+        .collect()
+}
+
+fn severity_style(severity: Severity) -> Style {
+    Style::default().fg(match severity {
+        Severity::Warning => Color::Yellow,
+        Severity::Error => Color::Red,
+    })
+}
+
+/// Per-source status: whether each manager was detected, what it returned
+/// and which operations failed.
+fn draw_diagnostics<B: Backend>(f: &mut Frame<B>, app: &App, frame: usize, area: Rect) {
+    let mut lines = Vec::new();
+    for source in app.registry.sources() {
+        let manager = source.manager();
+        let detected = app.profile.has(manager);
+        lines.push(Spans::from(vec![
+            Span::styled(
+                format!("{} {}", manager.icon(), manager.display_name()),
+                Style::default()
+                    .fg(manager.color())
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                if detected { "  detected" } else { "  not detected" },
+                Style::default().fg(if detected { Color::Green } else { Color::Gray }),
+            ),
+        ]));
+        if !detected {
+            continue;
+        }
+
+        for (operation, count, unit) in [
+            (Operation::Installed, app.stats.count(manager), "packages"),
+            (Operation::Outdated, app.stats.outdated_count(manager), "packages"),
+            (
+                Operation::History,
+                app.events.iter().filter(|e| e.source == manager).count(),
+                "events",
+            ),
+        ] {
+            let error = app
+                .errors
+                .iter()
+                .find(|e| e.source == manager && e.operation == operation);
+            let status = match error {
+                Some(error) if error.severity == Severity::Warning => Span::styled(
+                    format!("skipped: {}", error.error),
+                    severity_style(error.severity),
+                ),
+                Some(error) => Span::styled(
+                    format!("failed: {}", error.error),
+                    severity_style(error.severity),
+                ),
+                None => Span::styled(
+                    format!("ok, {} {}", count, unit),
+                    Style::default().fg(Color::Green),
+                ),
+            };
+            lines.push(Spans::from(vec![
+                Span::raw(format!("    {:<10}", operation.as_str())),
+                status,
+            ]));
+        }
+    }
+
+    let widget = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .title(panel_title(
+                    app,
+                    &[Panel::Packages, Panel::Outdated, Panel::Recent],
+                    "Diagnostics",
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                    frame,
+                ))
+                .borders(Borders::ALL),
+        );
+    f.render_widget(widget, area);
 }
 
 pub fn setup_terminal() -> io::Result<Terminal<CrosstermBackend<io::Stdout>>> {
@@ -1,11 +1,14 @@
 use tui::{
     style::{Color, Modifier, Style},
+    symbols::Marker,
     text::{Span, Spans},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, List, ListItem, Paragraph},
     Frame,
 };
 
-use crate::app::PackageStats;
+use crate::fetch::growth::{GrowthPoint, Period};
+use crate::fetch::stats::PackageStats;
+use crate::utils::format_timestamp;
 
 pub struct PackageTable {
     pub packages: Vec<String>,
@@ -15,8 +18,10 @@ pub struct PackageStatsWidget {
     pub stats: PackageStats,
 }
 
+/// Installed package counts over time, see [`crate::fetch::growth::replay`].
 pub struct PackageGraph {
-    pub data: Vec<u64>,
+    pub points: Vec<GrowthPoint>,
+    pub period: Period,
 }
 
 impl PackageTable {
@@ -47,7 +52,7 @@ impl PackageStatsWidget {
     }
 
     pub fn render<B: tui::backend::Backend>(&self, f: &mut Frame<B>, area: tui::layout::Rect) {
-        let stats_text = vec![
+        let mut stats_text = vec![
             Spans::from(vec![
                 Span::raw("Total packages: "),
                 Span::styled(
@@ -56,34 +61,14 @@ impl PackageStatsWidget {
                 ),
             ]),
             Spans::from(""),
-            Spans::from(vec![
-                Span::raw("Pacman: "),
-                Span::styled(
-                    format!("{}", self.stats.pacman),
-                    Style::default().fg(Color::Blue),
-                ),
-            ]),
-            Spans::from(vec![
-                Span::raw("AUR: "),
-                Span::styled(
-                    format!("{}", self.stats.aur),
-                    Style::default().fg(Color::Yellow),
-                ),
-            ]),
-            Spans::from(vec![
-                Span::raw("APT: "),
-                Span::styled(
-                    format!("{}", self.stats.apt),
-                    Style::default().fg(Color::Magenta),
-                ),
-            ]),
-            Spans::from(vec![
-                Span::raw("Flatpak: "),
-                Span::styled(
-                    format!("{}", self.stats.flatpak),
-                    Style::default().fg(Color::Cyan),
-                ),
-            ]),
+        ];
+        for (manager, count) in &self.stats.by_source {
+            stats_text.push(Spans::from(vec![
+                Span::raw(format!("{}: ", manager.display_name())),
+                Span::styled(format!("{}", count), Style::default().fg(manager.color())),
+            ]));
+        }
+        stats_text.extend(vec![
             Spans::from(""),
             Spans::from(vec![
                 Span::raw("Outdated: "),
@@ -92,7 +77,7 @@ impl PackageStatsWidget {
                     Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
                 ),
             ]),
-        ];
+        ]);
 
         let stats_widget = Paragraph::new(stats_text)
             .block(Block::default().title("Package Statistics").borders(Borders::ALL));
@@ -101,36 +86,71 @@ impl PackageStatsWidget {
 }
 
 impl PackageGraph {
-    pub fn new(data: Vec<u64>) -> Self {
-        Self { data }
+    pub fn new(points: Vec<GrowthPoint>, period: Period) -> Self {
+        Self { points, period }
     }
 
-    pub fn render<B: tui::backend::Backend>(&self, f: &mut Frame<B>, area: tui::layout::Rect) {
-        let max_value = self.data.iter().max().unwrap_or(&1);
-        let height = area.height as usize;
-        
-        let mut lines = Vec::new();
-        for i in 0..height {
-            let value = if i < self.data.len() {
-                self.data[i]
-            } else {
-                0
-            };
-            
-            let bar_length = if *max_value > 0 {
-                (value as f64 / *max_value as f64 * (area.width as f64 - 2.0)) as usize
-            } else {
-                0
-            };
-            
-            let bar = "█".repeat(bar_length);
-            let empty = "░".repeat((area.width as usize).saturating_sub(bar_length + 2));
-            
-            lines.push(Spans::from(format!("{}{}", bar, empty)));
+    /// Draws the package count over time as a line chart, with the first,
+    /// middle and last step labelled on the time axis.
+    pub fn render<B: tui::backend::Backend>(&self, f: &mut Frame<B>, area: tui::layout::Rect, title: Spans) {
+        let block = Block::default().title(title).borders(Borders::ALL);
+        if self.points.is_empty() {
+            let empty = Paragraph::new("No install or removal history found")
+                .style(Style::default().fg(Color::Gray))
+                .block(block);
+            f.render_widget(empty, area);
+            return;
         }
 
-        let graph_widget = Paragraph::new(lines)
-            .block(Block::default().title("Package Growth").borders(Borders::ALL));
-        f.render_widget(graph_widget, area);
+        let data: Vec<(f64, f64)> = self
+            .points
+            .iter()
+            .enumerate()
+            .map(|(i, point)| (i as f64, point.packages as f64))
+            .collect();
+        let min = self.points.iter().map(|p| p.packages).min().unwrap_or(0);
+        let max = self.points.iter().map(|p| p.packages).max().unwrap_or(0);
+        // Leave some room so a flat line does not sit on the border
+        let margin = ((max - min) / 10).max(1);
+        let (low, high) = (min.saturating_sub(margin), max + margin);
+
+        let last = self.points.len() - 1;
+        let date = |i: usize| {
+            let date = format_timestamp(self.points[i].start);
+            match self.period {
+                Period::Month => date[..7].to_string(),
+                _ => date[..10].to_string(),
+            }
+        };
+        let x_labels = if last == 0 {
+            vec![Span::raw(date(0))]
+        } else {
+            vec![Span::raw(date(0)), Span::raw(date(last / 2)), Span::raw(date(last))]
+        };
+
+        let dataset = Dataset::default()
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(Color::Cyan))
+            .data(&data);
+        let chart = Chart::new(vec![dataset])
+            .block(block)
+            .x_axis(
+                Axis::default()
+                    .style(Style::default().fg(Color::Gray))
+                    .bounds([0.0, last.max(1) as f64])
+                    .labels(x_labels),
+            )
+            .y_axis(
+                Axis::default()
+                    .style(Style::default().fg(Color::Gray))
+                    .bounds([low as f64, high as f64])
+                    .labels(vec![
+                        Span::raw(low.to_string()),
+                        Span::raw(((low + high) / 2).to_string()),
+                        Span::raw(high.to_string()),
+                    ]),
+            );
+        f.render_widget(chart, area);
     }
-}
\ No newline at end of file
+}
@@ -1,7 +1,11 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use rand::Rng;
 
+use crate::fetch::{PackageEvent, PackageInfo};
+
 pub fn generate_funny_comment(package_count: usize) -> String {
-    let comments = vec![
+    let comments = [
         format!("{} packages installed. System is happy.", package_count),
         format!("{} packages installed. Don't forget Ctrl+Z!", package_count),
         format!("{} packages. The system is getting fat.", package_count),
@@ -15,11 +19,6 @@ pub fn generate_funny_comment(package_count: usize) -> String {
     comments[index].clone()
 }
 
-pub fn format_package_name(name: &str) -> String {
-    // Clean up package names for display
-    name.split_whitespace().next().unwrap_or(name).to_string()
-}
-
 pub fn format_version(version: &str) -> String {
     if version.is_empty() {
         "unknown".to_string()
@@ -27,3 +26,87 @@ pub fn format_version(version: &str) -> String {
         version.to_string()
     }
 }
+
+/// Formats an outdated package's version change, e.g. `1.0-1 -> 1.1-1`.
+pub fn format_version_change(package: &PackageInfo) -> String {
+    match &package.available_version {
+        Some(new) => format!("{} -> {}", format_version(&package.version), new),
+        None => format_version(&package.version),
+    }
+}
+
+/// Formats the versions involved in an event: `1.0-1 -> 1.1-1` for
+/// upgrades and downgrades, otherwise the single version known.
+pub fn format_event_versions(event: &PackageEvent) -> String {
+    match (&event.old_version, &event.new_version) {
+        (Some(old), Some(new)) if old != new => format!("{} -> {}", old, new),
+        (_, Some(version)) | (Some(version), None) => version.clone(),
+        (None, None) => String::new(),
+    }
+}
+
+/// Formats a unix timestamp as `YYYY-MM-DD HH:MM` (UTC).
+pub fn format_timestamp(timestamp: i64) -> String {
+    let days = timestamp.div_euclid(86_400);
+    let secs = timestamp.rem_euclid(86_400);
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60
+    )
+}
+
+/// Formats how long ago `timestamp` was, relative to `now`: `just now`,
+/// `5m ago`, `2h ago`, `3d ago`, `6mo ago`, `2y ago`.
+pub fn format_relative_time(timestamp: i64, now: i64) -> String {
+    let secs = now - timestamp;
+    match secs {
+        ..=59 => "just now".to_string(),
+        60..=3599 => format!("{}m ago", secs / 60),
+        3600..=86_399 => format!("{}h ago", secs / 3600),
+        86_400..=2_591_999 => format!("{}d ago", secs / 86_400),
+        2_592_000..=31_535_999 => format!("{}mo ago", secs / 2_592_000),
+        _ => format!("{}y ago", secs / 31_536_000),
+    }
+}
+
+/// The current time as a unix timestamp.
+pub fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64)
+}
+
+/// Converts a (year, month, day) date to days since 1970-01-01.
+///
+/// Howard Hinnant's `days_from_civil` algorithm, the inverse of
+/// [`civil_from_days`].
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let month = i64::from(month);
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Converts days since 1970-01-01 to a (year, month, day) date.
+///
+/// Howard Hinnant's `civil_from_days` algorithm.
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}